@@ -3,11 +3,15 @@ use std::collections::HashMap;
 use cairo_rs::{
 	bigint,
 	hint_processor::hint_processor_definition::HintProcessor,
-	types::program::Program,
+	types::{
+		program::Program,
+		relocatable::{MaybeRelocatable, Relocatable},
+	},
 	vm::{
 		errors::{cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError},
 		hook::Hooks,
 		runners::cairo_runner::CairoRunner,
+		security::verify_secure_runner,
 		vm_core::VirtualMachine,
 	},
 };
@@ -15,14 +19,69 @@ use num_bigint::BigInt;
 use uuid::Uuid;
 
 use crate::{
-	hints::{output_buffer::EXECUTION_UUID_VAR_NAME, EXPECT_REVERT_FLAG, MOCK_CALL_KEY},
-	hooks::{HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+	compile::CompileOptions,
+	hints::{
+		check_expected_events, check_expected_messages_to_l1,
+		output_buffer::EXECUTION_UUID_VAR_NAME,
+		syscalls::{StarknetState, STARKNET_STATE_VAR_NAME},
+		EXPECT_REVERT_FLAG, MOCK_CALL_KEY,
+	},
+	hooks::{CallTracer, CALL_TRACER_VAR_NAME, HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+	io::compiled_programs::implicit_args,
+	trace::ExecutionTrace,
 };
 
+/// Layout programs are run with, unless configured otherwise
+pub const DEFAULT_LAYOUT: &str = "small";
+
+/// Name of the variable holding the layout in the execution scopes, so that the contracts
+/// called during an execution are run with the same layout
+pub const LAYOUT_VAR_NAME: &str = "layout";
+
+/// Name of the variable holding the options the contracts declared during an execution are
+/// compiled with, in the execution scopes
+pub const COMPILE_OPTIONS_VAR_NAME: &str = "compile_options";
+
+/// Builtins supported by the layouts, in the order a program must declare them
+const BUILTINS: [&str; 8] = [
+	"output",
+	"pedersen",
+	"range_check",
+	"ecdsa",
+	"bitwise",
+	"ec_op",
+	"keccak",
+	"poseidon",
+];
+
+/// Options a program is run with, see ``cairo_run``
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+	/// Number of steps after which the execution fails, only enforced when `Hooks` are applied
+	pub max_steps: u64,
+	/// Record the execution trace
+	pub trace_enabled: bool,
+	/// State the syscalls are served from
+	pub starknet_state: StarknetState,
+	/// Layout the program is run with, which sets the builtins available to it
+	/// (ie: "small", "all")
+	pub layout: String,
+	/// Run the program from `__start__` to `__end__` as it would be proved, see ``execute``
+	pub proof_mode: bool,
+	/// Options the contracts declared during the execution are compiled with
+	pub compile_options: CompileOptions,
+	/// Return values of the functions mocked from the start of the execution, by pc, see
+	/// ``mock_call``
+	pub mocks: HashMap<usize, BigInt>,
+}
+
 /// Execute a cairo program
 ///
-/// A `CairoRunner` and a `VirtualMachine` will be created to execute the given `Program`.
+/// A `CairoRunner` and a `VirtualMachine` will be created to execute the given `Program`
+/// with the given `RunOptions`.
 /// Hint and `Hooks` (if any) will be applied by the `VirtualMachine`
+/// The calls are only traced if a `CallTracer` is given and `Hooks` are applied.
+/// The entrypoint is called with `args` as explicit arguments.
 ///
 /// When no error is encountered, returns the `CairoRunner` and `VirtualMachine`.
 /// Otherwise, returns a `CairoRunError`
@@ -34,26 +93,235 @@ pub fn cairo_run(
 	hint_processor: &mut dyn HintProcessor,
 	execution_uuid: Uuid,
 	opt_hooks: Option<Hooks>,
-	max_steps: u64,
+	opt_call_tracer: Option<CallTracer>,
+	args: &[BigInt],
+	options: RunOptions,
 ) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
-	// 2023-01-06: FIXME: avoid hardcoded default layout & proof mode ?
-	let mut cairo_runner = CairoRunner::new(&program, "small", false)?;
-	let mut vm = VirtualMachine::new(program.prime, false, program.error_message_attributes);
-	let end = cairo_runner.initialize(&mut vm)?;
+	let proof_mode = options.proof_mode;
+	let (mut cairo_runner, mut vm, end) = initialize_runner(
+		program,
+		execution_uuid,
+		opt_hooks,
+		opt_call_tracer,
+		args,
+		options,
+	)?;
+
+	execute(&mut cairo_runner, &mut vm, end, hint_processor, proof_mode)?;
+
+	cairo_runner.relocate(&mut vm).map_err(CairoRunError::Trace)?;
+
+	Ok((cairo_runner, vm))
+}
+
+/// Execute a cairo program and record its relocated trace and memory
+///
+/// Unlike `cairo_run`, the trace and memory are recorded even when the execution fails,
+/// so that a failing test can be replayed up to the step where it failed.
+/// The trace is recorded whatever `options.trace_enabled` is.
+///
+/// Returns the recorded `ExecutionTrace` along with the execution error, if any.
+/// Otherwise, returns a `CairoRunError` if the execution could not even be started
+/// or its memory could not be relocated.
+pub fn cairo_run_recorded(
+	program: Program,
+	hint_processor: &mut dyn HintProcessor,
+	execution_uuid: Uuid,
+	opt_hooks: Option<Hooks>,
+	options: RunOptions,
+) -> Result<(ExecutionTrace, Option<CairoRunError>), CairoRunError> {
+	let proof_mode = options.proof_mode;
+	let options = RunOptions {
+		trace_enabled: true,
+		..options
+	};
+	let (mut cairo_runner, mut vm, end) =
+		initialize_runner(program, execution_uuid, opt_hooks, None, &[], options)?;
+
+	let execution_error =
+		execute(&mut cairo_runner, &mut vm, end, hint_processor, proof_mode).err();
+
+	cairo_runner.relocate(&mut vm).map_err(CairoRunError::Trace)?;
+
+	Ok((ExecutionTrace::from_runner(&cairo_runner), execution_error))
+}
+
+/// Create a `CairoRunner` and a `VirtualMachine` ready to execute the given `Program`
+///
+/// Returns the runner, the virtual machine and the address at which the execution ends.
+fn initialize_runner(
+	mut program: Program,
+	execution_uuid: Uuid,
+	opt_hooks: Option<Hooks>,
+	opt_call_tracer: Option<CallTracer>,
+	args: &[BigInt],
+	options: RunOptions,
+) -> Result<(CairoRunner, VirtualMachine, Relocatable), CairoRunError> {
+	// The builtins taken by the entrypoint are needed even without a `%builtins` directive
+	let opt_entrypoint = entrypoint_implicit_args(&program);
+	if let Some((_, implicit_args)) = &opt_entrypoint {
+		program.builtins = required_builtins(&program.builtins, implicit_args);
+	}
+
+	let mut cairo_runner = CairoRunner::new(&program, &options.layout, options.proof_mode)?;
+	let mut vm = VirtualMachine::new(
+		program.prime.clone(),
+		options.trace_enabled,
+		program.error_message_attributes.clone(),
+	);
+	// Implicit arguments are pushed in the order the entrypoint takes them, which may not be
+	// the order of the `%builtins` directive.
+	// In proof mode, the runner starts from `__start__`, which calls `main` itself.
+	let end = match opt_entrypoint {
+		Some((entrypoint, implicit_args)) if !options.proof_mode => initialize_entrypoint(
+			&mut cairo_runner,
+			&mut vm,
+			entrypoint,
+			&implicit_args,
+			EntrypointArgs::Felts(args),
+		)?,
+		_ => cairo_runner.initialize(&mut vm)?,
+	};
 
 	cairo_runner
 		.exec_scopes
 		.insert_value(EXECUTION_UUID_VAR_NAME, bigint!(execution_uuid.as_u128()));
 	if let Some(hooks) = opt_hooks {
 		cairo_runner.exec_scopes.insert_value(HOOKS_VAR_NAME, hooks);
-		cairo_runner.exec_scopes.insert_value(MAX_STEPS_VAR_NAME, max_steps);
+		cairo_runner.exec_scopes.insert_value(MAX_STEPS_VAR_NAME, options.max_steps);
+	}
+	if let Some(call_tracer) = opt_call_tracer {
+		cairo_runner.exec_scopes.insert_value(CALL_TRACER_VAR_NAME, call_tracer);
 	}
 
 	// Init exec context for mock_call
-	let hashmap: HashMap<usize, BigInt> = HashMap::new();
-	cairo_runner.exec_scopes.insert_value(MOCK_CALL_KEY, hashmap);
+	cairo_runner.exec_scopes.insert_value(MOCK_CALL_KEY, options.mocks);
 
-	let execution_result = cairo_runner.run_until_pc(end, &mut vm, hint_processor);
+	// Init exec context for the syscalls
+	cairo_runner
+		.exec_scopes
+		.insert_value(STARKNET_STATE_VAR_NAME, options.starknet_state);
+	cairo_runner.exec_scopes.insert_value(LAYOUT_VAR_NAME, options.layout);
+	cairo_runner
+		.exec_scopes
+		.insert_value(COMPILE_OPTIONS_VAR_NAME, options.compile_options);
+
+	Ok((cairo_runner, vm, end))
+}
+
+/// Returns the pc and the implicit arguments of the program entrypoint
+fn entrypoint_implicit_args(program: &Program) -> Option<(usize, Vec<String>)> {
+	let entrypoint = program.main?;
+	Some((entrypoint, function_implicit_args(program, entrypoint)?))
+}
+
+/// Returns the builtins of a program along with the builtins taken as implicit arguments
+/// (`<builtin>_ptr`) by its entrypoint, in the order the layouts define them
+pub(crate) fn required_builtins(
+	program_builtins: &[String],
+	implicit_args: &[String],
+) -> Vec<String> {
+	let mut builtins: Vec<String> = BUILTINS
+		.iter()
+		.filter(|builtin| {
+			program_builtins.iter().any(|program_builtin| program_builtin == *builtin)
+				|| implicit_args.contains(&format!("{builtin}_ptr"))
+		})
+		.map(|builtin| builtin.to_string())
+		.collect();
+	// Builtins we do not know the order of are kept last
+	builtins.extend(
+		program_builtins
+			.iter()
+			.filter(|builtin| !BUILTINS.contains(&builtin.as_str()))
+			.cloned(),
+	);
+	builtins
+}
+
+/// Returns the implicit arguments of the function starting at `pc`
+pub(crate) fn function_implicit_args(program: &Program, pc: usize) -> Option<Vec<String>> {
+	let (name, _) = program.identifiers.iter().find(|(_, identifier)| {
+		identifier.type_.as_deref() == Some("function") && identifier.pc == Some(pc)
+	})?;
+	Some(implicit_args(&program.identifiers, name))
+}
+
+/// Explicit arguments an entrypoint is called with
+pub(crate) enum EntrypointArgs<'a> {
+	/// Felts pushed as is, one per argument
+	Felts(&'a [BigInt]),
+	/// Calldata of a contract entry point, pushed as `(calldata_size, calldata)`
+	Calldata(&'a [BigInt]),
+}
+
+/// Initialize a `CairoRunner` to execute a function with the given implicit arguments
+///
+/// Entrypoints may take other implicit arguments than the `%builtins` directive declares, in
+/// another order, and functions taking explicit arguments cannot be run as a `main`, so the
+/// implicit arguments of the entrypoint are pushed one by one: builtins get the base of their
+/// segment and any other pointer (like the `syscall_ptr`) gets a segment of its own. The explicit
+/// arguments are pushed after them.
+///
+/// Returns the address at which the execution ends.
+pub(crate) fn initialize_entrypoint(
+	cairo_runner: &mut CairoRunner,
+	vm: &mut VirtualMachine,
+	entrypoint: usize,
+	implicit_args: &[String],
+	args: EntrypointArgs,
+) -> Result<Relocatable, CairoRunError> {
+	cairo_runner.initialize_builtins(vm)?;
+	cairo_runner.initialize_segments(vm, None);
+
+	let mut stack: Vec<MaybeRelocatable> = Vec::new();
+	for implicit_arg in implicit_args {
+		let builtin_stack = vm
+			.get_builtin_runners()
+			.iter()
+			.find(|(name, _)| format!("{name}_ptr") == *implicit_arg)
+			.map(|(_, builtin_runner)| builtin_runner.initial_stack());
+		match builtin_stack {
+			Some(builtin_stack) => stack.extend(builtin_stack),
+			None => stack.push(vm.add_memory_segment().into()),
+		}
+	}
+
+	match args {
+		EntrypointArgs::Felts(args) =>
+			stack.extend(args.iter().cloned().map(MaybeRelocatable::Int)),
+		EntrypointArgs::Calldata(calldata) => {
+			let calldata_ptr = vm.add_memory_segment();
+			for (i, value) in calldata.iter().enumerate() {
+				vm.insert_value(&(calldata_ptr + i), value)
+					.map_err(CairoRunError::VirtualMachine)?;
+			}
+			stack.push(MaybeRelocatable::Int(BigInt::from(calldata.len())));
+			stack.push(calldata_ptr.into());
+		},
+	}
+
+	let return_fp = vm.add_memory_segment();
+	let end =
+		cairo_runner.initialize_function_entrypoint(vm, entrypoint, stack, return_fp.into())?;
+	cairo_runner.initialize_vm(vm)?;
+
+	Ok(end)
+}
+
+/// Run an initialized `CairoRunner` until the given end address is reached
+///
+/// In `proof_mode`, the trace is then padded by looping on `__end__` until its length is a power
+/// of two and the layout has enough cells for the builtins used, and the run is checked the way
+/// the prover would: used cells, builtin segments and memory accesses.
+fn execute(
+	cairo_runner: &mut CairoRunner,
+	vm: &mut VirtualMachine,
+	end: Relocatable,
+	hint_processor: &mut dyn HintProcessor,
+	proof_mode: bool,
+) -> Result<(), CairoRunError> {
+	let execution_result = cairo_runner.run_until_pc(end, vm, hint_processor);
 	let should_revert = cairo_runner.exec_scopes.get_any_boxed_ref(EXPECT_REVERT_FLAG).is_ok();
 
 	match execution_result {
@@ -65,10 +333,23 @@ pub fn cairo_run(
 	}
 	.map_err(CairoRunError::VirtualMachine)?;
 
-	cairo_runner.end_run(false, false, &mut vm, hint_processor)?;
+	if !should_revert {
+		check_expected_events(&mut cairo_runner.exec_scopes)
+			.map_err(CairoRunError::VirtualMachine)?;
+		check_expected_messages_to_l1(&mut cairo_runner.exec_scopes)
+			.map_err(CairoRunError::VirtualMachine)?;
+	}
+
+	// The trace of a function run as entrypoint cannot be padded, it has no `__end__` to loop on
+	cairo_runner.end_run(!proof_mode, false, vm, hint_processor)?;
 	vm.verify_auto_deductions().map_err(CairoRunError::VirtualMachine)?;
 
-	cairo_runner.relocate(&mut vm).map_err(CairoRunError::Trace)?;
+	if proof_mode {
+		cairo_runner.check_used_cells(vm).map_err(CairoRunError::VirtualMachine)?;
+		cairo_runner.read_return_values(vm)?;
+		cairo_runner.finalize_segments(vm)?;
+		verify_secure_runner(cairo_runner, true, vm).map_err(CairoRunError::VirtualMachine)?;
+	}
 
-	Ok((cairo_runner, vm))
+	Ok(())
 }
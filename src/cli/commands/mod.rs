@@ -10,6 +10,10 @@ mod list;
 pub mod test;
 // clean module: contains everything related to the `Clean` command
 mod clean;
+// replay module: contains everything related to the `Replay` command
+mod replay;
+// run module: contains everything related to the `Run` command
+mod run;
 
 #[derive(Error, Debug)]
 // Todo: Think about a better way to bubble up the errors
@@ -21,6 +25,10 @@ pub enum CommandError {
 	TestCommandError(#[from] test::TestCommandError),
 	#[error(transparent)]
 	CleanCommandError(#[from] clean::CleanCommandError),
+	#[error(transparent)]
+	ReplayCommandError(#[from] replay::ReplayCommandError),
+	#[error(transparent)]
+	RunCommandError(#[from] run::RunCommandError),
 }
 
 /// Enum of all supported commands
@@ -32,6 +40,10 @@ pub enum Commands {
 	Test(test::TestArgs),
 	// Cleans the cache files
 	Clean(clean::CleanArgs),
+	// Step forwards and backwards through the recorded execution of a test
+	Replay(replay::ReplayArgs),
+	// Run a single entrypoint of a cairo program
+	Run(run::RunArgs),
 }
 
 /// Behaviour of a command
@@ -43,6 +55,8 @@ enum CommandOutputs {
 	List(list::ListOutput),
 	Test(test::TestOutput),
 	Clean(clean::CleanOutput),
+	Replay(replay::ReplayOutput),
+	Run(run::RunOutput),
 }
 
 /// The executed command output
@@ -57,6 +71,8 @@ impl Serialize for Output {
 			CommandOutputs::List(output) => output.serialize(serializer),
 			CommandOutputs::Test(output) => output.serialize(serializer),
 			CommandOutputs::Clean(output) => output.serialize(serializer),
+			CommandOutputs::Replay(output) => output.serialize(serializer),
+			CommandOutputs::Run(output) => output.serialize(serializer),
 		}
 	}
 }
@@ -67,6 +83,8 @@ impl fmt::Display for Output {
 			CommandOutputs::List(output) => output.fmt(f),
 			CommandOutputs::Test(output) => output.fmt(f),
 			CommandOutputs::Clean(output) => output.fmt(f),
+			CommandOutputs::Replay(output) => output.fmt(f),
+			CommandOutputs::Run(output) => output.fmt(f),
 		}
 	}
 }
@@ -80,6 +98,10 @@ impl CommandExecution<Output, CommandError> for Commands {
 				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Test(o))),
 			Commands::Clean(args) =>
 				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Clean(o))),
+			Commands::Replay(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Replay(o))),
+			Commands::Run(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Run(o))),
 		}
 	}
 }
@@ -0,0 +1,272 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	fmt::Display,
+	io::{self, BufRead, Write},
+	path::PathBuf,
+};
+
+use cairo_rs::{
+	types::{errors::program_errors, program::Program},
+	vm::errors::cairo_run_errors::CairoRunError,
+};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::CommandExecution;
+
+use crate::{
+	cairo_run::{cairo_run_recorded, RunOptions, DEFAULT_LAYOUT},
+	compile::{self, compile, CompileOptions},
+	hints::{
+		load_state_file,
+		output_buffer::{clear_buffer, init_buffer},
+		processor::setup_hint_processor,
+		syscalls::StarknetState,
+		test_layout, StateFileError,
+	},
+	hooks::setup_hooks,
+	trace::ExecutionTrace,
+};
+
+const HELP: &str = "\
+commands:
+  n, next [count]    step forward
+  p, prev [count]    step backward
+  g, goto <step|end> jump to a step
+  m, mem <address>   print the value stored at a relocated address
+  w, written <addr>  jump to the step that wrote a relocated address
+  h, help            print this message
+  q, quit            exit the replay";
+
+/// Replay command
+///
+/// Record the relocated trace and memory of a single test, then step forwards and
+/// backwards through its execution.
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+	/// Path to the cairo file containing the test
+	#[clap(short, long, value_hint=ValueHint::FilePath)]
+	pub file: PathBuf,
+	/// Name of the test entrypoint to replay
+	#[clap(short, long)]
+	pub test: String,
+	#[clap(short, long, default_value_t = 1000000)]
+	pub max_steps: u64,
+	/// Directory searched for imported modules
+	#[clap(long, value_hint=ValueHint::DirPath)]
+	pub cairo_path: Vec<PathBuf>,
+	/// JSON dump of the Starknet state the test starts from
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	pub state_file: Option<PathBuf>,
+	/// Layout the test is run with, unless it sets its own with the `layout` hint
+	#[clap(long, default_value = DEFAULT_LAYOUT)]
+	pub layout: String,
+}
+
+#[derive(Error, Debug)]
+// Todo: Maybe use anyhow at this level
+#[allow(clippy::large_enum_variant)]
+pub enum ReplayCommandError {
+	#[error(transparent)]
+	IO(#[from] io::Error),
+	#[error(transparent)]
+	Compile(#[from] compile::Error),
+	#[error(transparent)]
+	Program(#[from] program_errors::ProgramError),
+	#[error(transparent)]
+	CairoRun(#[from] CairoRunError),
+	#[error(transparent)]
+	StateFile(#[from] StateFileError),
+}
+
+/// Replay command output
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ReplayOutput {
+	pub test: String,
+	pub steps: usize,
+	pub error: Option<String>,
+}
+
+impl Display for ReplayOutput {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Replayed {} ({} steps)", self.test, self.steps)?;
+		if let Some(error) = &self.error {
+			writeln!(f, "Error: {error}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Interactive stepping through a recorded `ExecutionTrace`
+pub struct Replay {
+	trace: ExecutionTrace,
+	step: usize,
+}
+
+impl Replay {
+	pub fn new(trace: ExecutionTrace) -> Self {
+		Self { trace, step: 0 }
+	}
+
+	pub fn step(&self) -> usize {
+		self.step
+	}
+
+	/// Describe the current step: registers and the memory accessed by its instruction
+	pub fn describe(&self) -> String {
+		let entry = match self.trace.entries.get(self.step) {
+			Some(entry) => entry,
+			None => return String::from("empty trace"),
+		};
+
+		let mut description = format!(
+			"step {}/{}: pc={} ap={} fp={}",
+			self.step,
+			self.trace.len() - 1,
+			entry.pc,
+			entry.ap,
+			entry.fp
+		);
+		if let Some(accessed) = self.trace.accessed_addresses(self.step) {
+			for (operand, address) in [
+				("dst", accessed.dst),
+				("op0", accessed.op0),
+				("op1", accessed.op1),
+			] {
+				description.push_str(&format!(
+					"\n  {operand} [{address}] = {}",
+					self.format_memory(address)
+				));
+			}
+		}
+		description
+	}
+
+	/// Execute a single replay command
+	///
+	/// Returns the text to display, or `None` if the replay should stop.
+	pub fn execute(&mut self, command: &str) -> Option<String> {
+		let mut words = command.split_whitespace();
+		let output = match (words.next(), words.next()) {
+			(Some("q" | "quit"), _) => return None,
+			(None, _) | (Some("h" | "help"), _) => String::from(HELP),
+			(Some("n" | "next"), count) => match parse_count(count) {
+				Some(count) => self.goto(self.step.saturating_add(count)),
+				None => String::from("invalid step count"),
+			},
+			(Some("p" | "prev"), count) => match parse_count(count) {
+				Some(count) => self.goto(self.step.saturating_sub(count)),
+				None => String::from("invalid step count"),
+			},
+			(Some("g" | "goto"), Some("end")) => self.goto(self.trace.len().saturating_sub(1)),
+			(Some("g" | "goto"), Some(step)) => match step.parse() {
+				Ok(step) => self.goto(step),
+				Err(_) => format!("invalid step '{step}'"),
+			},
+			(Some("m" | "mem"), Some(address)) => match address.parse() {
+				Ok(address) => format!("[{address}] = {}", self.format_memory(address)),
+				Err(_) => format!("invalid address '{address}'"),
+			},
+			(Some("w" | "written"), Some(address)) => match address.parse() {
+				Ok(address) => match self.trace.first_access(address) {
+					Some(step) => self.goto(step),
+					None => format!("[{address}] is never accessed by an instruction"),
+				},
+				Err(_) => format!("invalid address '{address}'"),
+			},
+			(Some(command), _) => format!("unknown command '{command}'\n{HELP}"),
+		};
+		Some(output)
+	}
+
+	fn goto(&mut self, step: usize) -> String {
+		self.step = step.min(self.trace.len().saturating_sub(1));
+		self.describe()
+	}
+
+	fn format_memory(&self, address: usize) -> String {
+		match self.trace.memory_at(address) {
+			Some(value) => value.to_string(),
+			None => String::from("<unknown>"),
+		}
+	}
+}
+
+fn parse_count(count: Option<&str>) -> Option<usize> {
+	count.map_or(Some(1), |count| count.parse().ok())
+}
+
+impl CommandExecution<ReplayOutput, ReplayCommandError> for ReplayArgs {
+	fn exec(&self) -> Result<ReplayOutput, ReplayCommandError> {
+		let compile_options = CompileOptions {
+			cairo_path: self.cairo_path.clone(),
+			proof_mode: false,
+		};
+		let program_json = compile(&self.file, &compile_options)?;
+		let layout = test_layout(&program_json.hints, &format!("__main__.{}", self.test))
+			.unwrap_or_else(|| self.layout.clone());
+		let program = Program::from_json(program_json, Some(&self.test))?;
+		let mut starknet_state = StarknetState::default();
+		if let Some(state_file) = &self.state_file {
+			load_state_file(&mut starknet_state, state_file, &compile_options)?;
+		}
+
+		let execution_uuid = Uuid::new_v4();
+		init_buffer(execution_uuid);
+		let recorded = cairo_run_recorded(
+			program,
+			&mut setup_hint_processor(),
+			execution_uuid,
+			Some(setup_hooks()),
+			RunOptions {
+				max_steps: self.max_steps,
+				trace_enabled: true,
+				starknet_state,
+				layout,
+				proof_mode: false,
+				compile_options,
+				mocks: HashMap::new(),
+			},
+		);
+		clear_buffer(&execution_uuid);
+		let (trace, execution_error) = recorded?;
+
+		let error = execution_error.map(|e| format!("{e:?}"));
+		if let Some(error) = &error {
+			println!(
+				"Execution failed at step {}: {error}",
+				trace.len().saturating_sub(1)
+			);
+		}
+
+		let steps = trace.len();
+		let mut replay = Replay::new(trace);
+		println!("{}", replay.describe());
+
+		let stdin = io::stdin();
+		let mut lines = stdin.lock().lines();
+		loop {
+			print!("(replay) ");
+			io::stdout().flush()?;
+			let line = match lines.next() {
+				Some(line) => line?,
+				None => break,
+			};
+			match replay.execute(&line) {
+				Some(output) => println!("{output}"),
+				None => break,
+			}
+		}
+
+		Ok(ReplayOutput {
+			test: self.test.clone(),
+			steps,
+			error,
+		})
+	}
+}
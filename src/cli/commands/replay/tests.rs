@@ -0,0 +1,70 @@
+use num_bigint::BigInt;
+
+use super::{Replay, ReplayOutput};
+use crate::trace::{ExecutionTrace, TraceEntry};
+
+/// Three `[ap] = 5, ap++` steps writing to addresses 10, 11 and 12
+fn replay() -> Replay {
+	let mut memory = vec![None; 16];
+	memory[1] = Some(BigInt::from(0x480680017fff8000_u64));
+	memory[2] = Some(BigInt::from(5));
+	for address in 10..13 {
+		memory[address] = Some(BigInt::from(5));
+	}
+
+	Replay::new(ExecutionTrace {
+		entries: (10..13).map(|ap| TraceEntry { pc: 1, ap, fp: 10 }).collect(),
+		memory,
+	})
+}
+
+#[test]
+fn step_forwards_and_backwards() {
+	let mut replay = replay();
+
+	replay.execute("next");
+	assert_eq!(1, replay.step());
+	replay.execute("n 5");
+	assert_eq!(2, replay.step());
+	replay.execute("prev 2");
+	assert_eq!(0, replay.step());
+	replay.execute("goto end");
+	assert_eq!(2, replay.step());
+}
+
+#[test]
+fn jump_to_the_step_that_wrote_a_cell() {
+	let mut replay = replay();
+	replay.execute("goto end");
+
+	let output = replay.execute("written 11").unwrap();
+
+	assert_eq!(1, replay.step());
+	assert!(output.starts_with("step 1/2: pc=1 ap=11 fp=10"), "{output}");
+}
+
+#[test]
+fn print_memory_and_stop() {
+	let mut replay = replay();
+
+	assert_eq!(Some(String::from("[12] = 5")), replay.execute("mem 12"));
+	assert_eq!(
+		Some(String::from("[13] = <unknown>")),
+		replay.execute("m 13")
+	);
+	assert_eq!(None, replay.execute("quit"));
+}
+
+#[test]
+fn output_can_display_as_string() {
+	let output = ReplayOutput {
+		test: String::from("test_foo"),
+		steps: 42,
+		error: Some(String::from("boom")),
+	};
+
+	assert_eq!(
+		"Replayed test_foo (42 steps)\nError: boom\n",
+		format!("{output}")
+	);
+}
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, fmt::Display, io, path::PathBuf};
+
+use cairo_rs::{
+	types::{errors::program_errors, program::Program},
+	vm::errors::{cairo_run_errors::CairoRunError, runner_errors::RunnerError},
+};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::CommandExecution;
+
+use crate::{
+	cairo_run::{cairo_run, RunOptions, DEFAULT_LAYOUT},
+	compile::{self, compile, CompileOptions, Compiler},
+	hints::{
+		output_buffer::{clear_buffer, get_buffer, init_buffer},
+		processor::setup_hint_processor,
+		syscalls::StarknetState,
+	},
+	hooks::setup_hooks,
+	trace::ExecutionTrace,
+};
+
+/// Run command
+///
+/// Execute a single entrypoint of a cairo file, optionally exporting its relocated
+/// trace and memory in the cairo-lang binary format.
+/// In proof mode, the program is compiled and run as it would be proved: from `__start__` to
+/// `__end__`, with a padded trace and the checks of the prover. Only programs compiled with
+/// `cairo-compile` and run from `main` can be.
+#[derive(Args, Debug)]
+pub struct RunArgs {
+	/// Path to the cairo file to run
+	#[clap(short, long, value_hint=ValueHint::FilePath)]
+	pub file: PathBuf,
+	/// Name of the function to execute
+	#[clap(short, long, default_value = "main")]
+	pub entrypoint: String,
+	#[clap(short, long, default_value_t = 1000000)]
+	pub max_steps: u64,
+	/// Directory searched for imported modules
+	#[clap(long, value_hint=ValueHint::DirPath)]
+	pub cairo_path: Vec<PathBuf>,
+	/// Write the relocated trace to this file
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	pub trace_file: Option<PathBuf>,
+	/// Write the relocated memory to this file
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	pub memory_file: Option<PathBuf>,
+	/// Layout the program is run with, which sets the builtins available to it
+	#[clap(long, default_value = DEFAULT_LAYOUT)]
+	pub layout: String,
+	/// Run the program in proof mode, `main` being called from `__start__`
+	#[clap(long)]
+	pub proof_mode: bool,
+}
+
+#[derive(Error, Debug)]
+// Todo: Maybe use anyhow at this level
+#[allow(clippy::large_enum_variant)]
+pub enum RunCommandError {
+	#[error("failed to write file '{0}': {1}")]
+	WriteFile(PathBuf, io::Error),
+	#[error("only main can be run in proof mode, got entrypoint '{0}'")]
+	ProofModeEntrypoint(String),
+	#[error("{0} is a Starknet contract, which cannot be compiled in proof mode")]
+	ProofModeStarknetContract(PathBuf),
+	#[error(transparent)]
+	Compile(#[from] compile::Error),
+	#[error(transparent)]
+	Program(#[from] program_errors::ProgramError),
+	#[error(transparent)]
+	CairoRun(#[from] CairoRunError),
+	#[error(transparent)]
+	Runner(#[from] RunnerError),
+}
+
+/// Run command output
+#[derive(Debug, Serialize, PartialEq, Eq, Default)]
+pub struct RunOutput {
+	/// Output captured from the hints
+	pub captured_stdout: String,
+	/// Output of the program, as written by the output builtin
+	pub program_output: String,
+	pub trace_file: Option<PathBuf>,
+	pub memory_file: Option<PathBuf>,
+}
+
+impl Display for RunOutput {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}{}", self.captured_stdout, self.program_output)?;
+		if let Some(trace_file) = &self.trace_file {
+			writeln!(f, "trace written to {}", trace_file.display())?;
+		}
+		if let Some(memory_file) = &self.memory_file {
+			writeln!(f, "memory written to {}", memory_file.display())?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<RunOutput, RunCommandError> for RunArgs {
+	fn exec(&self) -> Result<RunOutput, RunCommandError> {
+		// `__start__` only calls `main`
+		if self.proof_mode && self.entrypoint != "main" {
+			return Err(RunCommandError::ProofModeEntrypoint(
+				self.entrypoint.clone(),
+			))
+		}
+		// `starknet-compile` has no proof mode
+		if self.proof_mode && Compiler::for_file(&self.file)? == Compiler::Starknet {
+			return Err(RunCommandError::ProofModeStarknetContract(
+				self.file.clone(),
+			))
+		}
+		let compile_options = CompileOptions {
+			cairo_path: self.cairo_path.clone(),
+			proof_mode: self.proof_mode,
+		};
+		let program_json = compile(&self.file, &compile_options)?;
+		let program = Program::from_json(program_json, Some(&self.entrypoint))?;
+
+		let execution_uuid = Uuid::new_v4();
+		init_buffer(execution_uuid);
+		let res_cairo_run = cairo_run(
+			program,
+			&mut setup_hint_processor(),
+			execution_uuid,
+			Some(setup_hooks()),
+			None,
+			&[],
+			RunOptions {
+				max_steps: self.max_steps,
+				trace_enabled: self.trace_file.is_some(),
+				starknet_state: StarknetState::default(),
+				layout: self.layout.clone(),
+				proof_mode: self.proof_mode,
+				// Contracts are compiled by `starknet-compile`, which has no proof mode
+				compile_options: CompileOptions {
+					proof_mode: false,
+					..compile_options
+				},
+				mocks: HashMap::new(),
+			},
+		);
+		// Safe to unwrap as long as `init_buffer` has been called before
+		let captured_stdout = get_buffer(&execution_uuid).unwrap();
+		clear_buffer(&execution_uuid);
+		let (mut runner, mut vm) = res_cairo_run?;
+
+		let trace = ExecutionTrace::from_runner(&runner);
+		if let Some(trace_file) = &self.trace_file {
+			trace
+				.write_binary_trace(trace_file)
+				.map_err(|e| RunCommandError::WriteFile(trace_file.clone(), e))?;
+		}
+		if let Some(memory_file) = &self.memory_file {
+			trace
+				.write_binary_memory(memory_file)
+				.map_err(|e| RunCommandError::WriteFile(memory_file.clone(), e))?;
+		}
+
+		Ok(RunOutput {
+			captured_stdout,
+			program_output: runner.get_output(&mut vm)?,
+			trace_file: self.trace_file.clone(),
+			memory_file: self.memory_file.clone(),
+		})
+	}
+}
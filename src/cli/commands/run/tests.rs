@@ -0,0 +1,107 @@
+use std::{fs, path::PathBuf};
+
+use assert_matches::assert_matches;
+
+use super::{RunArgs, RunCommandError, RunOutput};
+use crate::{cairo_run::DEFAULT_LAYOUT, cli::commands::CommandExecution};
+
+#[test]
+fn output_can_display_as_string() {
+	let output = RunOutput {
+		captured_stdout: String::new(),
+		program_output: String::from("50\n"),
+		trace_file: Some(PathBuf::from("/trace.bin")),
+		memory_file: None,
+	};
+
+	assert_eq!("50\ntrace written to /trace.bin\n", format!("{output}"));
+}
+
+#[test]
+fn run_writes_trace_and_memory_files() -> Result<(), RunCommandError> {
+	let dir = std::env::temp_dir().join("cairo_foundry_test");
+	fs::create_dir_all(&dir).unwrap();
+	let trace_file = dir.join("run_writes_trace.bin");
+	let memory_file = dir.join("run_writes_memory.bin");
+
+	let output = RunArgs {
+		file: PathBuf::from("./test_cairo_contracts/test_valid_program.cairo"),
+		entrypoint: String::from("main"),
+		max_steps: 1000000,
+		cairo_path: Vec::new(),
+		trace_file: Some(trace_file.clone()),
+		memory_file: Some(memory_file.clone()),
+		layout: String::from(DEFAULT_LAYOUT),
+		proof_mode: false,
+	}
+	.exec()?;
+
+	assert_eq!("50\n", output.program_output);
+	let trace = fs::read(trace_file).unwrap();
+	assert!(!trace.is_empty());
+	assert_eq!(0, trace.len() % 24);
+	let memory = fs::read(memory_file).unwrap();
+	assert!(!memory.is_empty());
+	assert_eq!(0, memory.len() % 40);
+
+	Ok(())
+}
+
+#[test]
+fn run_in_proof_mode_pads_the_trace() -> Result<(), RunCommandError> {
+	let dir = std::env::temp_dir().join("cairo_foundry_test");
+	fs::create_dir_all(&dir).unwrap();
+	let trace_file = dir.join("run_in_proof_mode_trace.bin");
+
+	let output = RunArgs {
+		file: PathBuf::from("./test_cairo_contracts/test_valid_program.cairo"),
+		entrypoint: String::from("main"),
+		max_steps: 1000000,
+		cairo_path: Vec::new(),
+		trace_file: Some(trace_file.clone()),
+		memory_file: None,
+		layout: String::from(DEFAULT_LAYOUT),
+		proof_mode: true,
+	}
+	.exec()?;
+
+	assert_eq!("50\n", output.program_output);
+	let steps = fs::read(trace_file).unwrap().len() / 24;
+	assert!(steps.is_power_of_two(), "{steps} steps");
+
+	Ok(())
+}
+
+#[test]
+fn run_in_proof_mode_requires_main() {
+	let result = RunArgs {
+		file: PathBuf::from("./test_cairo_contracts/test_valid_program.cairo"),
+		entrypoint: String::from("test_array_sum_positive"),
+		max_steps: 1000000,
+		cairo_path: Vec::new(),
+		trace_file: None,
+		memory_file: None,
+		layout: String::from(DEFAULT_LAYOUT),
+		proof_mode: true,
+	}
+	.exec();
+
+	assert_matches!(result, Err(RunCommandError::ProofModeEntrypoint(_)));
+}
+
+#[test]
+fn run_in_proof_mode_rejects_starknet_contracts() {
+	let result = RunArgs {
+		file: PathBuf::from("./test_cairo_contracts/test_setup.cairo"),
+		entrypoint: String::from("main"),
+		max_steps: 1000000,
+		cairo_path: Vec::new(),
+		trace_file: None,
+		memory_file: None,
+		layout: String::from(DEFAULT_LAYOUT),
+		proof_mode: true,
+	}
+	.exec();
+
+	assert_matches!(result, Err(RunCommandError::ProofModeStarknetContract(_)));
+}
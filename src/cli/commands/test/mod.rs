@@ -7,34 +7,57 @@ use cairo_rs::{
 	vm::{
 		errors::{cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError},
 		hook::Hooks,
+		runners::cairo_runner::CairoRunner,
+		vm_core::VirtualMachine,
 	},
 };
 use clap::{Args, ValueHint};
 use colored::Colorize;
+use num_bigint::BigInt;
 use serde::Serialize;
 
-use std::{fmt::Display, io, path::PathBuf, sync::Arc, time::Instant};
+use std::{collections::HashMap, fmt::Display, io, path::PathBuf, time::Instant};
 use thiserror::Error;
 use uuid::Uuid;
 
 use super::{list::path_is_valid_directory, CommandExecution};
 
 use crate::{
-	cairo_run::cairo_run,
-	compile::{self, compile},
+	cairo_run::{cairo_run, RunOptions, DEFAULT_LAYOUT},
+	compile::{
+		self,
+		cache::{default_corpus_root, CacheError},
+		compile, CompileOptions,
+	},
+	config::{Config, ConfigError},
+	fuzz::{
+		shrink, shrink_sequence, Call, CallContext, Corpus, FeltGenerator, FuzzConfig,
+		TargetContract, DEFAULT_FUZZ_RUNS, DEFAULT_INVARIANT_DEPTH, MAX_REJECTS_PER_RUN,
+	},
 	hints::{
+		fuzz_ranges,
 		hint_processor::function_like_hint_processor::FunctionLikeHintProcessor,
+		invariant_target, load_state_file,
 		output_buffer::{clear_buffer, get_buffer, init_buffer},
+		parametrize_rows,
 		processor::setup_hint_processor,
-		EXPECT_REVERT_FLAG,
+		syscalls::{get_starknet_state, StarknetState},
+		test_layout, StateFileError, ASSUME_REJECTED_FLAG, EXPECTED_EVENTS_VAR_NAME,
+		EXPECTED_MESSAGES_TO_L1_VAR_NAME, EXPECT_REVERT_FLAG, MOCK_CALL_KEY,
 	},
-	hooks,
+	hooks::{setup_hooks, CallTracer},
 	io::{
-		compiled_programs::{list_test_entrypoints, ListTestEntrypointsError},
+		compiled_programs::{
+			explicit_args, identifier_size, list_functions, list_test_entrypoints,
+			ListTestEntrypointsError,
+		},
 		test_files::{list_test_files, ListTestsFilesError},
 	},
 };
 
+/// Function run once before the tests of a file, see ``run_setup``
+const SETUP_FUNCTION: &str = "__setup__";
+
 /// Enum containing the possible errors that you may encounter in the ``Test`` module
 #[derive(Error, Debug)]
 // Todo: Maybe use anyhow at this level
@@ -51,6 +74,8 @@ pub enum TestCommandError {
 	#[error(transparent)]
 	Compile(#[from] compile::Error),
 	#[error(transparent)]
+	Config(#[from] ConfigError),
+	#[error(transparent)]
 	Program(#[from] program_errors::ProgramError),
 	#[error(transparent)]
 	CairoRun(#[from] CairoRunError),
@@ -58,10 +83,17 @@ pub enum TestCommandError {
 	ListTestsFiles(#[from] ListTestsFilesError),
 	#[error(transparent)]
 	ListTestEntripoints(#[from] ListTestEntrypointsError),
+	#[error(transparent)]
+	StateFile(#[from] StateFileError),
+	#[error(transparent)]
+	Cache(#[from] CacheError),
 }
 
 /// Structure containing the path to a cairo directory.
 /// Used to execute all the tests files contained in this directory
+///
+/// Tests cannot be run in proof mode: test functions are run as entrypoints, without the
+/// `__start__` and `__end__` a proved program is run between, see `run --proof-mode`.
 #[derive(Args, Debug)]
 pub struct TestArgs {
 	/// Path to a cairo directory
@@ -69,19 +101,74 @@ pub struct TestArgs {
 	pub root: PathBuf,
 	#[clap(short, long, default_value_t = 1000000)]
 	pub max_steps: u64,
+	/// Print the tree of function calls made by each test
+	#[clap(long)]
+	pub trace_calls: bool,
+	/// Directory searched for imported modules, in addition to the ones of the config file
+	#[clap(long, value_hint=ValueHint::DirPath)]
+	pub cairo_path: Vec<PathBuf>,
+	/// JSON dump of the Starknet state every test starts from
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	pub state_file: Option<PathBuf>,
+	/// Number of random inputs each test taking arguments is run with
+	#[clap(long, default_value_t = DEFAULT_FUZZ_RUNS)]
+	pub fuzz_runs: usize,
+	/// Seed of the random inputs, to reproduce a failure (random by default)
+	#[clap(long)]
+	pub fuzz_seed: Option<u64>,
+	/// Number of calls made to the target contract in each run of an invariant test
+	#[clap(long, default_value_t = DEFAULT_INVARIANT_DEPTH)]
+	pub invariant_depth: usize,
+	/// Layout the tests are run with, overriding the one of the config file ("small" by default)
+	#[clap(long)]
+	pub layout: Option<String>,
+	/// Run the whole suite once per layout of this comma-separated list
+	#[clap(long, value_delimiter = ',', conflicts_with = "layout")]
+	pub layout_matrix: Vec<String>,
+}
+
+/// Options applied to every test run by the `test` command
+#[derive(Debug, Clone)]
+pub struct TestOptions {
+	pub max_steps: u64,
+	/// Capture the tree of function calls made by each test
+	pub trace_calls: bool,
+	/// State the syscalls of each test are served from
+	pub starknet_state: StarknetState,
+	pub fuzz: FuzzConfig,
+	/// Options the contracts declared by the tests and the target contracts of invariant tests
+	/// are compiled with
+	pub compile_options: CompileOptions,
+	/// Layout the tests are run with, unless they set their own with the `layout` hint
+	pub layout: String,
+	/// Directory holding the failing inputs of the fuzzed tests, see ``Corpus``
+	pub corpus_root: PathBuf,
+	/// Return values of the functions mocked from the start of each test, by pc
+	pub mocks: HashMap<usize, BigInt>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum TestStatus {
 	SUCCESS,
 	FAILURE,
 }
 
+/// Status of a test case, as reported in the output of the command
+#[derive(Debug, Serialize)]
+pub struct TestCase {
+	/// Name of the test, followed by the number of the row for parametrized tests
+	/// (`test_add[1]`)
+	pub name: String,
+	pub success: TestStatus,
+}
+
 /// Structure representing the result of one or multiple test.
 /// Contains the output of the test, as well as the status.
+/// `cases` lists the test cases reported separately, like the rows of a parametrized test.
 pub struct TestResult {
 	pub output: String,
 	pub success: TestStatus,
+	pub cases: Vec<TestCase>,
 }
 
 impl From<(String, TestStatus)> for TestResult {
@@ -89,38 +176,40 @@ impl From<(String, TestStatus)> for TestResult {
 		Self {
 			output: from.0,
 			success: from.1,
+			cases: Vec::new(),
 		}
 	}
 }
 
+/// Test cases of a test file run with a given layout
+#[derive(Debug, Serialize)]
+pub struct TestFileOutput {
+	pub path: PathBuf,
+	pub layout: String,
+	pub cases: Vec<TestCase>,
+}
+
 /// Execute command output
+///
+/// The output of the tests is printed as they run, only their status is kept.
 #[derive(Debug, Serialize, Default)]
-pub struct TestOutput(String);
+pub struct TestOutput {
+	pub files: Vec<TestFileOutput>,
+}
 
 impl Display for TestOutput {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", &self.0)
+	fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Ok(())
 	}
 }
 
-/// Create a new ``Hooks`` object, with the followings hooks:
-/// - pre_step_instruction
-/// - post_step_instruction
-///
-/// see [src/hooks.rs]
-fn setup_hooks() -> Hooks {
-	Hooks::new(
-		Arc::new(hooks::pre_step_instruction),
-		Arc::new(hooks::post_step_instruction),
-	)
-}
-
 /// Compile a cairo file, returning a truple
 /// (path_to_original_code, path_to_compiled_code, entrypoints)
 fn compile_and_list_entrypoints(
 	path_to_code: PathBuf,
+	compile_options: &CompileOptions,
 ) -> Result<(PathBuf, ProgramJson, Vec<String>), TestCommandError> {
-	let program_json = compile(&path_to_code)?;
+	let program_json = compile(&path_to_code, compile_options)?;
 	let entrypoints = list_test_entrypoints(&program_json)?;
 	Ok((path_to_code, program_json, entrypoints))
 }
@@ -134,58 +223,188 @@ fn purge_hint_buffer(execution_uuid: &Uuid, output: &mut String) {
 	clear_buffer(execution_uuid);
 }
 
+/// Result of a single execution of a test entrypoint
+#[allow(clippy::large_enum_variant)]
+enum Execution {
+	Passed(CairoRunner, VirtualMachine),
+	Skipped,
+	/// The input was discarded by `assume` or `fuzz_range`
+	Rejected,
+	Failed(String),
+}
+
+/// Execute a test entrypoint once, with the given explicit arguments.
+fn execute_test(
+	program: Program,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	execution_uuid: Uuid,
+	opt_call_tracer: Option<CallTracer>,
+	args: &[BigInt],
+) -> Execution {
+	let res_cairo_run = cairo_run(
+		program,
+		hint_processor,
+		execution_uuid,
+		hooks,
+		opt_call_tracer,
+		args,
+		RunOptions {
+			max_steps: options.max_steps,
+			trace_enabled: false,
+			starknet_state: options.starknet_state.clone(),
+			layout: options.layout.clone(),
+			proof_mode: false,
+			compile_options: options.compile_options.clone(),
+			mocks: options.mocks.clone(),
+		},
+	);
+	match res_cairo_run {
+		Ok((runner, vm)) => Execution::Passed(runner, vm),
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) if custom_error_message == "skip" => Execution::Skipped,
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) if custom_error_message == ASSUME_REJECTED_FLAG => Execution::Rejected,
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) if custom_error_message == EXPECT_REVERT_FLAG => Execution::Failed(String::from(
+			"execution did not revert while expect_revert() was specified",
+		)),
+		// Errors raised by our own hints are meant to be read as is
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) => Execution::Failed(custom_error_message),
+		Err(e) => Execution::Failed(format!("{e:?}")),
+	}
+}
+
 /// Execute a single test.
 /// Take a program and a test name as input, search for this entrypoint in the compiled file
 /// and execute it.
 /// It will then return a TestResult, representing the output of the test.
+/// If `options.trace_calls` is set, the tree of function calls made by the test is captured
+/// along with its output.
+/// Tests taking explicit arguments are fuzzed, see ``fuzz_single_entrypoint``, unless they are
+/// parametrized, see ``parametrized_single_entrypoint``.
+/// Invariants are checked against random calls, see ``invariant_single_entrypoint``.
+/// The `layout` hint of the test overrides `options.layout`.
 fn test_single_entrypoint(
 	program: ProgramJson,
+	path_to_code: &PathBuf,
 	test_entrypoint: &str,
 	hint_processor: &mut FunctionLikeHintProcessor,
 	hooks: Option<Hooks>,
-	max_steps: u64,
+	options: &TestOptions,
 ) -> Result<TestResult, TestCommandError> {
+	let function = format!("__main__.{test_entrypoint}");
+	let test_options;
+	let options = match test_layout(&program.hints, &function) {
+		Some(layout) => {
+			test_options = TestOptions {
+				layout,
+				..options.clone()
+			};
+			&test_options
+		},
+		None => options,
+	};
+
+	if test_entrypoint.starts_with("invariant_") {
+		return invariant_single_entrypoint(program, test_entrypoint, hint_processor, hooks, options)
+	}
+
+	let args = explicit_args(&program.identifiers, &function);
+	if let Some(rows) = parametrize_rows(&program.hints, &function) {
+		return parametrized_single_entrypoint(
+			program,
+			test_entrypoint,
+			rows,
+			hint_processor,
+			hooks,
+			options,
+		)
+	}
+	if !args.is_empty() {
+		return fuzz_single_entrypoint(
+			program,
+			path_to_code,
+			test_entrypoint,
+			&args,
+			hint_processor,
+			hooks,
+			options,
+		)
+	}
+
+	let opt_call_tracer = options.trace_calls.then(|| CallTracer::new(list_functions(&program)));
+	let program = Program::from_json(program, Some(test_entrypoint))?;
+	Ok(run_test_case(
+		program,
+		test_entrypoint,
+		&[],
+		hint_processor,
+		hooks,
+		options,
+		opt_call_tracer,
+	))
+}
+
+/// Execute a test case once with the given explicit arguments, and report it under `case_name`
+fn run_test_case(
+	program: Program,
+	case_name: &str,
+	args: &[BigInt],
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	opt_call_tracer: Option<CallTracer>,
+) -> TestResult {
 	let start = Instant::now();
 	let mut output = String::new();
 	let execution_uuid = Uuid::new_v4();
 	init_buffer(execution_uuid);
 
-	let program = Program::from_json(program, Some(test_entrypoint))?;
-
-	let res_cairo_run = cairo_run(program, hint_processor, execution_uuid, hooks, max_steps);
+	let execution = execute_test(
+		program,
+		hint_processor,
+		hooks,
+		options,
+		execution_uuid,
+		opt_call_tracer,
+		args,
+	);
 	let duration = start.elapsed();
-	let (opt_runner_and_output, test_success) = match res_cairo_run {
-		Ok(res) => {
+	let (opt_runner_and_output, test_success) = match execution {
+		Execution::Passed(runner, vm) => {
 			output.push_str(&format!(
 				"[{}] {} ({:?})\n",
 				"OK".green(),
-				test_entrypoint,
+				case_name,
 				duration
 			));
-			(Some(res), TestStatus::SUCCESS)
+			(Some((runner, vm)), TestStatus::SUCCESS)
 		},
-		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
-			custom_error_message,
-		))) if custom_error_message == "skip" => {
-			output.push_str(&format!("[{}] {}\n", "SKIPPED".yellow(), test_entrypoint,));
+		Execution::Skipped => {
+			output.push_str(&format!("[{}] {}\n", "SKIPPED".yellow(), case_name,));
 			(None, TestStatus::SUCCESS)
 		},
-		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
-			custom_error_message,
-		))) if custom_error_message == EXPECT_REVERT_FLAG => {
+		Execution::Rejected => {
 			output.push_str(&format!(
-				"[{}] {}\nError: execution did not revert while expect_revert() was specified\n\n",
+				"[{}] {}\nError: assume() rejected the execution, only fuzzed tests can discard their inputs\n\n",
 				"FAILED".red(),
-				test_entrypoint,
+				case_name,
 			));
 			(None, TestStatus::FAILURE)
 		},
-		Err(e) => {
+		Execution::Failed(error) => {
 			output.push_str(&format!(
-				"[{}] {}\nError: {:?}\n\n",
+				"[{}] {}\nError: {}\n\n",
 				"FAILED".red(),
-				test_entrypoint,
-				e
+				case_name,
+				error
 			));
 			(None, TestStatus::FAILURE)
 		},
@@ -194,7 +413,7 @@ fn test_single_entrypoint(
 	purge_hint_buffer(&execution_uuid, &mut output);
 	let (mut runner, mut vm) = match opt_runner_and_output {
 		Some(runner_and_vm) => runner_and_vm,
-		None => return Ok((output, test_success).into()),
+		None => return case_result(output, case_name, test_success),
 	};
 
 	// Display the execution output if present
@@ -211,12 +430,481 @@ fn test_single_entrypoint(
 	};
 
 	output.push('\n');
-	Ok((output, test_success).into())
+	case_result(output, case_name, test_success)
+}
+
+/// Returns the result of a single test case, reported under `case_name`
+fn case_result(output: String, case_name: &str, success: TestStatus) -> TestResult {
+	TestResult {
+		output,
+		success,
+		cases: vec![TestCase {
+			name: case_name.to_string(),
+			success,
+		}],
+	}
+}
+
+/// Execute a test once per row of its `parametrize` hint.
+///
+/// Each row is reported as a separate test case, named after its number starting from 1
+/// (`test_add[1]`).
+/// The test fails if any of its rows does.
+fn parametrized_single_entrypoint(
+	program: ProgramJson,
+	test_entrypoint: &str,
+	rows: Vec<Vec<BigInt>>,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+) -> Result<TestResult, TestCommandError> {
+	// Struct arguments take as many felts as their size
+	let args_size = program
+		.identifiers
+		.get(&format!("__main__.{test_entrypoint}.Args"))
+		.map_or(0, |args| identifier_size(&program.identifiers, args));
+	let opt_functions = options.trace_calls.then(|| list_functions(&program));
+	let program = Program::from_json(program, Some(test_entrypoint))?;
+
+	let mut output = String::new();
+	let mut test_success = TestStatus::SUCCESS;
+	let mut cases = Vec::new();
+	for (i, row) in rows.iter().enumerate() {
+		let case_name = format!("{test_entrypoint}[{}]", i + 1);
+		let result = if row.len() == args_size {
+			run_test_case(
+				program.clone(),
+				&case_name,
+				row,
+				hint_processor,
+				hooks.clone(),
+				options,
+				opt_functions.clone().map(CallTracer::new),
+			)
+		} else {
+			let error = format!(
+				"[{}] {}\nError: expected {} felts as arguments, got {}\n\n",
+				"FAILED".red(),
+				case_name,
+				args_size,
+				row.len()
+			);
+			case_result(error, &case_name, TestStatus::FAILURE)
+		};
+		output.push_str(&result.output);
+		if result.success == TestStatus::FAILURE {
+			test_success = TestStatus::FAILURE;
+		}
+		cases.extend(result.cases);
+	}
+	Ok(TestResult {
+		output,
+		success: test_success,
+		cases,
+	})
+}
+
+/// Execute a test taking explicit arguments with `options.fuzz.runs` random inputs.
+///
+/// Only felt arguments are supported. The inputs the test failed with on previous runs are
+/// replayed first, from the corpus of the test. The test stops at the first input it fails
+/// with, which is shrunk, added to the corpus and reported along with the seed the inputs were
+/// generated from.
+/// Inputs rejected by `assume` or `fuzz_range` are not counted as runs, the test failing when
+/// too many of them are.
+fn fuzz_single_entrypoint(
+	program: ProgramJson,
+	path_to_code: &PathBuf,
+	test_entrypoint: &str,
+	args: &[(String, String)],
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+) -> Result<TestResult, TestCommandError> {
+	let start = Instant::now();
+	let mut output = String::new();
+
+	if let Some((name, cairo_type)) = args.iter().find(|(_, cairo_type)| cairo_type != "felt") {
+		output.push_str(&format!(
+			"[{}] {}\nError: cannot fuzz argument '{}' of type {}, only felts are supported\n\n",
+			"FAILED".red(),
+			test_entrypoint,
+			name,
+			cairo_type
+		));
+		return Ok((output, TestStatus::FAILURE).into())
+	}
+
+	let prime = program.prime.clone();
+	let ranges = fuzz_ranges(&program.hints, &format!("__main__.{test_entrypoint}"));
+	let ranges: Vec<_> = args.iter().map(|(name, _)| ranges.get(name).cloned()).collect();
+	let mut generator = FeltGenerator::new(options.fuzz.seed, prime.clone());
+	let program = Program::from_json(program, Some(test_entrypoint))?;
+	let mut corpus = Corpus::open(&options.corpus_root, path_to_code, test_entrypoint)?;
+	let execution_uuid = Uuid::new_v4();
+	init_buffer(execution_uuid);
+
+	let mut execute = |input: &[BigInt]| {
+		execute_test(
+			program.clone(),
+			hint_processor,
+			hooks.clone(),
+			options,
+			execution_uuid,
+			None,
+			input,
+		)
+	};
+
+	let mut replayed_inputs = corpus
+		.inputs()
+		.iter()
+		.filter(|input| input.len() == args.len())
+		.cloned()
+		.collect::<Vec<_>>()
+		.into_iter();
+
+	// Inputs rejected by `assume` are replaced by new ones, without being counted as runs
+	let max_rejects = options.fuzz.runs * MAX_REJECTS_PER_RUN;
+	let mut rejects = 0;
+	let mut run = 0;
+	let mut failure = None;
+	loop {
+		// Inputs of the corpus come without a run number
+		let (opt_run, input) = match replayed_inputs.next() {
+			Some(input) => (None, input),
+			None if run < options.fuzz.runs => (Some(run + 1), generator.input(&ranges)),
+			None => break,
+		};
+		match execute(&input) {
+			Execution::Passed(_, _) => {
+				clear_buffer(&execution_uuid);
+				run = opt_run.unwrap_or(run);
+			},
+			Execution::Skipped => {
+				output.push_str(&format!("[{}] {}\n", "SKIPPED".yellow(), test_entrypoint));
+				clear_buffer(&execution_uuid);
+				return Ok((output, TestStatus::SUCCESS).into())
+			},
+			Execution::Rejected => {
+				clear_buffer(&execution_uuid);
+				if opt_run.is_some() {
+					rejects += 1;
+				}
+				if rejects > max_rejects {
+					output.push_str(&format!(
+						"[{}] {}\nError: too many inputs rejected by assume() or fuzz_range() ({} rejected, {} passed)\n\n",
+						"FAILED".red(),
+						test_entrypoint,
+						rejects,
+						run
+					));
+					return Ok((output, TestStatus::FAILURE).into())
+				}
+			},
+			Execution::Failed(_) => {
+				clear_buffer(&execution_uuid);
+				failure = Some((opt_run, input));
+				break
+			},
+		}
+	}
+
+	let (opt_run, input) = match failure {
+		Some(failure) => failure,
+		None => {
+			output.push_str(&format!(
+				"[{}] {} (runs: {}, rejected: {}, {:?})\n",
+				"OK".green(),
+				test_entrypoint,
+				options.fuzz.runs,
+				rejects,
+				start.elapsed()
+			));
+			return Ok((output, TestStatus::SUCCESS).into())
+		},
+	};
+
+	// Inputs of the corpus were already shrunk
+	let (input, origin) = match opt_run {
+		Some(run) => {
+			let shrunk = shrink(&input, &prime, |candidate| {
+				let failed = matches!(execute(candidate), Execution::Failed(_));
+				clear_buffer(&execution_uuid);
+				failed
+			});
+			corpus.add(shrunk.clone())?;
+			(
+				shrunk,
+				format!(
+					"run {}/{}, seed {}",
+					run, options.fuzz.runs, options.fuzz.seed
+				),
+			)
+		},
+		None => (input, String::from("from corpus")),
+	};
+
+	// Execute the failing input once more, to report its error and captured output
+	let error = match execute(&input) {
+		Execution::Failed(error) => error,
+		_ => String::from("the test did not fail again with the same input"),
+	};
+	let input = args
+		.iter()
+		.zip(&input)
+		.map(|((name, _), felt)| format!("{name}={felt}"))
+		.collect::<Vec<_>>()
+		.join(", ");
+	output.push_str(&format!(
+		"[{}] {}\nFalsifying input ({}): {}\nError: {}\n\n",
+		"FAILED".red(),
+		test_entrypoint,
+		origin,
+		input,
+		error
+	));
+	purge_hint_buffer(&execution_uuid, &mut output);
+	Ok((output, TestStatus::FAILURE).into())
+}
+
+/// Check an invariant after each call of `options.fuzz.runs` random sequences of calls to the
+/// external functions of its target contract.
+///
+/// The target contract is set with the `target_contract` hint and deployed once, every sequence
+/// starting from the resulting state. Calls are made with random felts and may take up to
+/// `options.max_steps` each, the ones which fail being reverted and left out of the sequence. The
+/// first sequence breaking the invariant is shrunk and reported along with the seed it was
+/// generated from.
+fn invariant_single_entrypoint(
+	program: ProgramJson,
+	invariant: &str,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+) -> Result<TestResult, TestCommandError> {
+	let start = Instant::now();
+	let mut output = String::new();
+	let failed = |mut output: String, error: String| -> Result<TestResult, TestCommandError> {
+		output.push_str(&format!(
+			"[{}] {}\nError: {}\n\n",
+			"FAILED".red(),
+			invariant,
+			error
+		));
+		Ok((output, TestStatus::FAILURE).into())
+	};
+
+	let function = format!("__main__.{invariant}");
+	let (path, constructor_calldata) = match invariant_target(&program.hints, &function) {
+		Some(target) => target,
+		None =>
+			return failed(
+				output,
+				String::from("no target contract, set one with target_contract(path)"),
+			),
+	};
+	let mut initial_state = options.starknet_state.clone();
+	let target = match TargetContract::deploy(
+		&mut initial_state,
+		&path,
+		&constructor_calldata,
+		CallContext {
+			layout: options.layout.clone(),
+			compile_options: options.compile_options.clone(),
+			max_steps: options.max_steps,
+		},
+	) {
+		Ok(target) => target,
+		Err(e) => return failed(output, format!("failed to deploy the target contract: {e}")),
+	};
+	if target.functions.is_empty() {
+		return failed(
+			output,
+			format!(
+				"{} has no external function taking only felts to call",
+				path.display()
+			),
+		)
+	}
+
+	// The invariant gets the address of the target contract, if it takes an argument
+	let args = match explicit_args(&program.identifiers, &function).len() {
+		0 => Vec::new(),
+		_ => vec![target.address.clone()],
+	};
+	let prime = program.prime.clone();
+	let mut generator = FeltGenerator::new(options.fuzz.seed, prime.clone());
+	let program = Program::from_json(program, Some(invariant))?;
+	let execution_uuid = Uuid::new_v4();
+	init_buffer(execution_uuid);
+
+	// Returns the error of the invariant if it does not hold in the given state
+	let mut invariant_options = options.clone();
+	let mut check = |state: &StarknetState| {
+		clear_buffer(&execution_uuid);
+		invariant_options.starknet_state = state.clone();
+		match execute_test(
+			program.clone(),
+			hint_processor,
+			hooks.clone(),
+			&invariant_options,
+			execution_uuid,
+			None,
+			&args,
+		) {
+			Execution::Failed(error) => Some(error),
+			_ => None,
+		}
+	};
+
+	if let Some(error) = check(&initial_state) {
+		purge_hint_buffer(&execution_uuid, &mut output);
+		return failed(
+			output,
+			format!("the invariant does not hold once the target contract is deployed: {error}"),
+		)
+	}
+
+	let mut calls = 0;
+	let mut failure = None;
+	'runs: for run in 1..=options.fuzz.runs {
+		let mut state = initial_state.clone();
+		let mut sequence = Vec::new();
+		for _ in 0..options.fuzz.depth {
+			let call = target.random_call(&mut generator);
+			if target.call(&mut state, &call).is_err() {
+				continue
+			}
+			calls += 1;
+			sequence.push(call);
+			if check(&state).is_some() {
+				failure = Some((run, sequence));
+				break 'runs
+			}
+		}
+	}
+
+	let (run, sequence) = match failure {
+		Some(failure) => failure,
+		None => {
+			clear_buffer(&execution_uuid);
+			output.push_str(&format!(
+				"[{}] {} (runs: {}, calls: {}, {:?})\n",
+				"OK".green(),
+				invariant,
+				options.fuzz.runs,
+				calls,
+				start.elapsed()
+			));
+			return Ok((output, TestStatus::SUCCESS).into())
+		},
+	};
+
+	// Returns the number of calls after which the invariant is broken, and its error
+	let mut replay = |sequence: &[Call]| {
+		let mut state = initial_state.clone();
+		for (i, call) in sequence.iter().enumerate() {
+			if target.call(&mut state, call).is_err() {
+				continue
+			}
+			if let Some(error) = check(&state) {
+				return Some((i + 1, error))
+			}
+		}
+		None
+	};
+	let sequence = shrink_sequence(&sequence, &prime, |candidate| replay(candidate).is_some());
+
+	// Replay the shrunk sequence once more, to report its error and captured output
+	let (length, error) = replay(&sequence).unwrap_or((
+		sequence.len(),
+		String::from("the invariant held with the same calls"),
+	));
+	output.push_str(&format!(
+		"[{}] {}\nFailing call sequence (run {}/{}, seed {}):\n",
+		"FAILED".red(),
+		invariant,
+		run,
+		options.fuzz.runs,
+		options.fuzz.seed
+	));
+	for call in &sequence[..length] {
+		output.push_str(&format!("  {}\n", target.format_call(call)));
+	}
+	output.push_str(&format!("Error: {error}\n\n"));
+	purge_hint_buffer(&execution_uuid, &mut output);
+	Ok((output, TestStatus::FAILURE).into())
+}
+
+/// Run the `__setup__` function of a test file.
+///
+/// Returns the options the tests of the file are run with: they start from the Starknet state
+/// the setup left, with the calls it mocked.
+/// Events and messages to L1 sent by the setup are left out, so that tests only see their own.
+/// The memory of the setup is not kept.
+/// Expectations (`expect_revert`, `expect_events` and `expect_message_to_l1`) only apply to the
+/// execution they are set in, so the setup fails if it sets any.
+fn run_setup(
+	program_json: ProgramJson,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Hooks,
+	options: &TestOptions,
+) -> Result<Result<TestOptions, String>, TestCommandError> {
+	let program = Program::from_json(program_json, Some(SETUP_FUNCTION))?;
+	let execution_uuid = Uuid::new_v4();
+	init_buffer(execution_uuid);
+
+	let execution = execute_test(
+		program,
+		hint_processor,
+		Some(hooks),
+		options,
+		execution_uuid,
+		None,
+		&[],
+	);
+	clear_buffer(&execution_uuid);
+	let mut runner = match execution {
+		Execution::Passed(runner, _) => runner,
+		Execution::Failed(error) => return Ok(Err(error)),
+		Execution::Skipped | Execution::Rejected =>
+			return Ok(Err(String::from("the setup cannot be skipped"))),
+	};
+
+	for (variable, cheatcode) in [
+		(EXPECT_REVERT_FLAG, "expect_revert"),
+		(EXPECTED_EVENTS_VAR_NAME, "expect_events"),
+		(EXPECTED_MESSAGES_TO_L1_VAR_NAME, "expect_message_to_l1"),
+	] {
+		if runner.exec_scopes.get_any_boxed_ref(variable).is_ok() {
+			return Ok(Err(format!(
+				"{cheatcode}() cannot be used in {SETUP_FUNCTION}, expectations only apply to the execution they are set in"
+			)))
+		}
+	}
+
+	let mut state = std::mem::take(
+		get_starknet_state(&mut runner.exec_scopes).map_err(CairoRunError::VirtualMachine)?,
+	);
+	state.events.clear();
+	state.messages_to_l1.clear();
+	let mocks = runner
+		.exec_scopes
+		.get::<HashMap<usize, BigInt>>(MOCK_CALL_KEY)
+		.map_err(CairoRunError::VirtualMachine)?;
+	Ok(Ok(TestOptions {
+		starknet_state: state,
+		mocks,
+		..options.clone()
+	}))
 }
 
 /// Run every test contained in a cairo file.
 /// this function will deserialize a compiled cairo file, and call ``test_single_entrypoint`` on
 /// each entrypoint provided.
+/// If the file has a `__setup__` function, it is run first and every test starts from the
+/// Starknet state and mocks it left, see ``run_setup``. No test is run if the setup fails.
 /// It will then return a TestResult corresponding to all the tests (SUCCESS if all the test
 /// succeded, FAILURE otherwise).
 fn run_tests_for_one_file(
@@ -225,66 +913,153 @@ fn run_tests_for_one_file(
 	program_json: ProgramJson,
 	test_entrypoints: Vec<String>,
 	hooks: Hooks,
-	max_steps: u64,
+	options: &TestOptions,
 ) -> Result<TestResult, TestCommandError> {
-	let output = format!("Running tests in file {}\n", path_to_original.display());
+	let mut output = format!("Running tests in file {}\n", path_to_original.display());
+
+	let mut file_options = options.clone();
+	if program_json.identifiers.contains_key(&format!("__main__.{SETUP_FUNCTION}")) {
+		match run_setup(program_json.clone(), hint_processor, hooks.clone(), options)? {
+			Ok(setup_options) => file_options = setup_options,
+			Err(error) => {
+				output.push_str(&format!(
+					"[{}] {}\nError: {}\n\n",
+					"FAILED".red(),
+					SETUP_FUNCTION,
+					error
+				));
+				return Ok(case_result(output, SETUP_FUNCTION, TestStatus::FAILURE))
+			},
+		}
+	}
+
 	let res = test_entrypoints
 		.into_iter()
 		.map(|test_entrypoint| {
-			test_single_entrypoint(
+			let mut result = test_single_entrypoint(
 				program_json.clone(),
+				&path_to_original,
 				&test_entrypoint,
 				hint_processor,
 				Some(hooks.clone()),
-				max_steps,
-			)
+				&file_options,
+			)?;
+			// Tests which do not report their own cases are a single case
+			if result.cases.is_empty() {
+				result.cases.push(TestCase {
+					name: test_entrypoint,
+					success: result.success,
+				});
+			}
+			Ok(result)
 		})
 		.collect::<Result<Vec<_>, TestCommandError>>()?
 		.into_iter()
-		.fold((output, TestStatus::SUCCESS), |mut a, b| {
-			a.0.push_str(&b.output);
-			// SUCCESS if both a.1 and b.success are SUCCESS, otherwise, FAILURE
-			a.1 = if a.1 == TestStatus::SUCCESS && b.success == TestStatus::SUCCESS {
-				TestStatus::SUCCESS
-			} else {
-				TestStatus::FAILURE
-			};
-			a
-		});
-	Ok(res.into())
+		.fold(
+			(output, TestStatus::SUCCESS).into(),
+			|mut a: TestResult, b| {
+				a.output.push_str(&b.output);
+				// SUCCESS if both a.success and b.success are SUCCESS, otherwise, FAILURE
+				a.success = if a.success == TestStatus::SUCCESS && b.success == TestStatus::SUCCESS
+				{
+					TestStatus::SUCCESS
+				} else {
+					TestStatus::FAILURE
+				};
+				a.cases.extend(b.cases);
+				a
+			},
+		);
+	Ok(res)
+}
+
+impl TestArgs {
+	/// Layouts the suite is run with: the ones of `--layout-matrix` if any, otherwise the one of
+	/// `--layout`, of the config file, or the default one, by order of precedence
+	fn layouts(&self, config: &Config) -> Vec<String> {
+		if !self.layout_matrix.is_empty() {
+			return self.layout_matrix.clone()
+		}
+		let layout = self
+			.layout
+			.clone()
+			.or_else(|| config.layout.clone())
+			.unwrap_or_else(|| DEFAULT_LAYOUT.to_string());
+		vec![layout]
+	}
 }
 
 impl CommandExecution<TestOutput, TestCommandError> for TestArgs {
 	fn exec(&self) -> Result<TestOutput, TestCommandError> {
+		let config = Config::load(&self.root)?;
+		let layouts = self.layouts(&config);
+		let compile_options = CompileOptions {
+			cairo_path: config.cairo_path.into_iter().chain(self.cairo_path.clone()).collect(),
+			proof_mode: false,
+		};
+		let mut starknet_state = StarknetState::default();
+		if let Some(state_file) = &self.state_file {
+			load_state_file(&mut starknet_state, state_file, &compile_options)?;
+		}
+		let corpus_root = default_corpus_root()?;
+		// Every layout of the matrix is run with the same inputs
+		let fuzz = FuzzConfig {
+			runs: self.fuzz_runs,
+			seed: self.fuzz_seed.unwrap_or_else(rand::random),
+			depth: self.invariant_depth,
+		};
+
 		// Declare hints
 		let mut hint_processor = setup_hint_processor();
 		let hooks = setup_hooks();
 
-		list_test_files(&self.root)?
-			//.into_par_iter()
-			.into_iter()
-			.map(compile_and_list_entrypoints)
-			.map(|res| -> Result<TestResult, TestCommandError> {
-				match res {
-					Ok((path_to_original, program_json, test_entrypoints)) =>
-						run_tests_for_one_file(
-							&mut hint_processor,
-							path_to_original,
-							program_json,
-							test_entrypoints,
-							hooks.clone(),
-							self.max_steps,
-						),
-					Err(err) => Err(err),
-				}
-			})
-			.for_each(|test_result| match test_result {
-				Ok(result) => {
-					println!("{}", result.output);
-				},
-				Err(err) => println!("{}", format!("Error: {err}").red()),
-			});
+		let mut files = Vec::new();
+		for layout in layouts {
+			if !self.layout_matrix.is_empty() {
+				println!("{}", format!("Running tests with layout {layout}\n").bold());
+			}
+			let options = TestOptions {
+				max_steps: self.max_steps,
+				trace_calls: self.trace_calls,
+				starknet_state: starknet_state.clone(),
+				fuzz,
+				compile_options: compile_options.clone(),
+				layout: layout.clone(),
+				corpus_root: corpus_root.clone(),
+				mocks: HashMap::new(),
+			};
+
+			list_test_files(&self.root)?
+				//.into_par_iter()
+				.into_iter()
+				.map(|path_to_code| {
+					let (path_to_original, program_json, test_entrypoints) =
+						compile_and_list_entrypoints(path_to_code, &compile_options)?;
+					let result = run_tests_for_one_file(
+						&mut hint_processor,
+						path_to_original.clone(),
+						program_json,
+						test_entrypoints,
+						hooks.clone(),
+						&options,
+					)?;
+					Ok((path_to_original, result))
+				})
+				.for_each(
+					|test_result: Result<_, TestCommandError>| match test_result {
+						Ok((path, result)) => {
+							println!("{}", result.output);
+							files.push(TestFileOutput {
+								path,
+								layout: layout.clone(),
+								cases: result.cases,
+							});
+						},
+						Err(err) => println!("{}", format!("Error: {err}").red()),
+					},
+				);
+		}
 
-		Ok(Default::default())
+		Ok(TestOutput { files })
 	}
 }
@@ -1,33 +1,164 @@
-use crate::cli::commands::{test::TestArgs, CommandExecution};
-use std::path::PathBuf;
+use crate::{
+	cairo_run::DEFAULT_LAYOUT,
+	cli::commands::{test::TestArgs, CommandExecution},
+	compile::CompileOptions,
+	fuzz::{FuzzConfig, DEFAULT_FUZZ_RUNS, DEFAULT_INVARIANT_DEPTH},
+	hints::syscalls::StarknetState,
+};
+use regex::Regex;
+use rstest::rstest;
+use std::{collections::HashMap, path::PathBuf};
+use uuid::Uuid;
 
 use super::{
-	compile_and_list_entrypoints, setup_hint_processor, setup_hooks, test_single_entrypoint,
-	TestCommandError, TestResult,
+	compile_and_list_entrypoints, run_tests_for_one_file, setup_hint_processor, setup_hooks,
+	test_single_entrypoint, TestCommandError, TestOptions, TestResult, TestStatus,
 };
 
+/// Default options of the `test` command, with a fixed fuzzing seed and an empty corpus
+pub fn test_options(max_steps: u64) -> TestOptions {
+	TestOptions {
+		max_steps,
+		trace_calls: false,
+		starknet_state: StarknetState::default(),
+		fuzz: FuzzConfig {
+			runs: DEFAULT_FUZZ_RUNS,
+			seed: 0,
+			depth: DEFAULT_INVARIANT_DEPTH,
+		},
+		compile_options: CompileOptions::default(),
+		layout: String::from(DEFAULT_LAYOUT),
+		corpus_root: std::env::temp_dir()
+			.join("cairo_foundry_test")
+			.join("corpus")
+			.join(Uuid::new_v4().to_string()),
+		mocks: HashMap::new(),
+	}
+}
+
 pub fn run_single_test(
 	test_name: &str,
 	test_path: &PathBuf,
 	max_steps: u64,
 ) -> Result<TestResult, TestCommandError> {
-	let (_, program_json, _) = compile_and_list_entrypoints(test_path.to_owned())?;
+	run_single_test_with_options(test_name, test_path, &test_options(max_steps))
+}
+
+pub fn run_single_test_with_options(
+	test_name: &str,
+	test_path: &PathBuf,
+	options: &TestOptions,
+) -> Result<TestResult, TestCommandError> {
+	let (_, program_json, _) =
+		compile_and_list_entrypoints(test_path.to_owned(), &options.compile_options)?;
 
 	test_single_entrypoint(
 		program_json,
+		test_path,
 		test_name,
 		&mut setup_hint_processor(),
 		Some(setup_hooks()),
-		max_steps,
+		options,
 	)
 }
 
+#[rstest]
+#[case("test_pedersen", TestStatus::SUCCESS)]
+#[case("test_range_check", TestStatus::SUCCESS)]
+#[case("test_range_check_and_pedersen", TestStatus::SUCCESS)]
+#[case("test_range_check_is_enforced", TestStatus::FAILURE)]
+fn builtins_are_inferred_from_implicit_args(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("./test_cairo_contracts/test_implicit_builtins.cairo");
+	let result = run_single_test(test_name, &path, 1000000)?;
+	assert_eq!(expected_success, result.success, "{}", result.output);
+	Ok(())
+}
+
+#[test]
+fn tests_start_from_the_setup_state() -> Result<(), TestCommandError> {
+	let path = PathBuf::from("./test_cairo_contracts/test_setup.cairo");
+	let (path, program_json, entrypoints) =
+		compile_and_list_entrypoints(path, &CompileOptions::default())?;
+
+	let result = run_tests_for_one_file(
+		&mut setup_hint_processor(),
+		path,
+		program_json,
+		entrypoints,
+		setup_hooks(),
+		&test_options(1000000),
+	)?;
+	assert_eq!(TestStatus::SUCCESS, result.success, "{}", result.output);
+	Ok(())
+}
+
+#[test]
+fn setup_cannot_set_expectations() -> Result<(), TestCommandError> {
+	let path = PathBuf::from("./test_cairo_contracts/test_setup_expectations.cairo");
+	let (path, program_json, entrypoints) =
+		compile_and_list_entrypoints(path, &CompileOptions::default())?;
+
+	let result = run_tests_for_one_file(
+		&mut setup_hint_processor(),
+		path,
+		program_json,
+		entrypoints,
+		setup_hooks(),
+		&test_options(1000000),
+	)?;
+	assert_eq!(TestStatus::FAILURE, result.success, "{}", result.output);
+	assert!(
+		result.output.contains("expect_revert() cannot be used in __setup__"),
+		"{}",
+		result.output
+	);
+	Ok(())
+}
+
 #[test]
 fn test_cairo_contracts() {
 	TestArgs {
 		root: PathBuf::from("./test_cairo_contracts"),
 		max_steps: 1000000,
+		trace_calls: false,
+		cairo_path: Vec::new(),
+		state_file: None,
+		fuzz_runs: DEFAULT_FUZZ_RUNS,
+		fuzz_seed: Some(0),
+		invariant_depth: DEFAULT_INVARIANT_DEPTH,
+		layout: None,
+		layout_matrix: Vec::new(),
 	}
 	.exec()
 	.unwrap();
 }
+
+#[test]
+fn test_trace_calls() -> Result<(), TestCommandError> {
+	let path = PathBuf::from("./test_cairo_contracts/test_valid_program.cairo");
+	let (_, program_json, _) =
+		compile_and_list_entrypoints(path.clone(), &CompileOptions::default())?;
+
+	let result = test_single_entrypoint(
+		program_json,
+		&path,
+		"test_array_sum_positive",
+		&mut setup_hint_processor(),
+		Some(setup_hooks()),
+		&TestOptions {
+			trace_calls: true,
+			..test_options(1000000)
+		},
+	)?;
+
+	// The array is a pointer to a segment allocated at run time, whatever its index
+	let call = Regex::new(r"├─ array_sum\(\d+:\d+, 3\)").unwrap();
+	let nested_call = Regex::new(r"│  ├─ array_sum\(\d+:\d+, 2\)").unwrap();
+	assert!(call.is_match(&result.output), "{}", result.output);
+	assert!(nested_call.is_match(&result.output), "{}", result.output);
+	assert!(result.output.contains("└─ ← (50)"), "{}", result.output);
+	Ok(())
+}
@@ -5,20 +5,20 @@ mod tests;
 use std::env;
 
 use std::{
+	collections::HashMap,
 	fmt::Debug,
 	fs::{self, read_to_string, File},
 	io,
-	path::PathBuf,
+	path::{Path, PathBuf},
 };
 
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
 use serde_json::{self, Value};
-use std::{
-	collections::hash_map::DefaultHasher,
-	hash::{Hash, Hasher},
-};
 
 // CacheDirNotSupported is a top level struct and not an enum variant because
 // it's converted elsewhere to other errors using [#from] and we want to be
@@ -52,10 +52,31 @@ pub enum CacheError {
 	SerializeError(serde_json::Error),
 	#[error("failed to read file '{0}': {1}")]
 	ReadFile(String, io::Error),
+	#[error("unsupported cache format version {0}, expected {CACHE_FORMAT_VERSION}")]
+	UnsupportedVersion(u64),
 }
 
 pub const JSON_FILE_EXTENTION: &str = "json";
 pub const CAIRO_FOUNDRY_CACHE_DIR: &str = "cairo-foundry-cache";
+/// Subdirectory of the cache holding the failing inputs of fuzzed tests
+pub const CORPUS_DIR: &str = "corpus";
+/// Version of the `CompileCacheItem` format. Cache items written with another version are
+/// discarded. Bump it whenever the format changes.
+pub const CACHE_FORMAT_VERSION: u64 = 3;
+/// Hash recorded for a file which did not exist when the program was compiled, but would have
+/// been imported if it did
+pub const MISSING_FILE_HASH: &str = "";
+/// Prefix of the files generated by `starknet-compile`, which do not exist on disk
+const AUTOGEN_PREFIX: &str = "autogen/";
+
+lazy_static! {
+	/// Imports of a cairo file, like `from starkware.cairo.common.alloc import alloc` or
+	/// `import a.b`
+	static ref IMPORT_REGEX: Regex =
+		Regex::new(r"(?m)^\s*(?:from\s+([\w.]+)\s+import\b|import\s+([\w.]+))").unwrap();
+	/// Locations in compiler error messages, like `path/to/file.cairo:12:5`
+	static ref ERROR_LOCATION_REGEX: Regex = Regex::new(r"([^\s:]+\.cairo):\d+:\d+").unwrap();
+}
 
 #[cfg(not(test))]
 pub fn cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
@@ -67,18 +88,44 @@ pub fn cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
 	Ok(env::temp_dir().join("cairo-foundry-tests"))
 }
 
-// TODO: support cashing compile errors to avoid recompiling files that failed before
+/// Everything the output of a compilation depends on, besides imported files
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompileCacheKey {
+	/// SHA-256 of the compiled file
+	pub hash: String,
+	/// Version of the compiler, as printed by `cairo-compile --version`
+	pub compiler_version: String,
+	/// Arguments given to the compiler, besides the compiled file
+	pub compile_args: Vec<String>,
+}
+
+/// Outcome of a compilation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompileOutput {
+	// TODO: make it a ProgramJson, we're not using it right now because
+	// it doesn't implement Serialize
+	Program(Value),
+	/// The compiler failed, with the given error message and exit status
+	Error {
+		stderr: String,
+		status: i32,
+	},
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CompileCacheItem {
-	pub hash: u64,
-	// TODO: make program_json: ProgramJson, we're not using it right now because
-	// it doesn't implement Serialize
-	pub program_json: Value,
+	pub version: u64,
+	pub key: CompileCacheKey,
+	pub output: CompileOutput,
+	/// Hashes of the source files the output depends on, including imported ones
+	#[serde(default)]
+	pub dependencies: HashMap<PathBuf, String>,
 }
 
 impl PartialEq for CompileCacheItem {
 	fn eq(&self, other: &Self) -> bool {
-		self.hash == other.hash
+		self.key == other.key
 	}
 }
 
@@ -87,9 +134,17 @@ impl CompileCacheItem {
 		let file_content = read_to_string(path)
 			.map_err(|e| CacheError::ReadFile(path.as_path().display().to_string(), e))?;
 
-		let data = serde_json::from_str::<CompileCacheItem>(file_content.as_str())
-			.map_err(|e| CacheError::DeserializeError(file_content, e))?;
-		Ok(data)
+		let data = serde_json::from_str::<Value>(file_content.as_str())
+			.map_err(|e| CacheError::DeserializeError(file_content.clone(), e))?;
+
+		// Items written before the format was versioned have no version at all
+		let version = data["version"].as_u64().unwrap_or_default();
+		if version != CACHE_FORMAT_VERSION {
+			return Err(CacheError::UnsupportedVersion(version))
+		}
+
+		serde_json::from_value::<CompileCacheItem>(data)
+			.map_err(|e| CacheError::DeserializeError(file_content, e))
 	}
 
 	pub fn write(&self, cache_path: &PathBuf) -> Result<(), CacheError> {
@@ -98,16 +153,135 @@ impl CompileCacheItem {
 
 		serde_json::to_writer(file, self).map_err(CacheError::SerializeError)
 	}
+
+	/// Returns true if the item was compiled with the given key and none of the source files
+	/// the program depends on changed since then
+	pub fn is_valid_for(&self, key: &CompileCacheKey) -> bool {
+		self.key == *key && self.dependencies_are_unchanged()
+	}
+
+	/// Returns true if none of the source files the program depends on changed since it was
+	/// compiled, and none of the missing ones was created
+	pub fn dependencies_are_unchanged(&self) -> bool {
+		self.dependencies.iter().all(|(path, hash)| match hash.as_str() {
+			MISSING_FILE_HASH => !path.exists(),
+			hash => hash_file(path).map_or(false, |current| current == hash),
+		})
+	}
 }
 
-pub fn get_compile_cache_path(path_to_cairo_file: &PathBuf) -> Result<PathBuf, CacheError> {
-	// Retrieve only the file name to create a clean compiled file name.
-	let filename = path_to_cairo_file.file_stem().and_then(|f| f.to_str()).ok_or_else(|| {
-		CacheError::StemlessFile(path_to_cairo_file.as_path().display().to_string())
-	})?;
+/// Hash every source file a compiled program depends on.
+///
+/// The dependencies are the files imported by the compiled file, see ``hash_imports``, along
+/// with the input files of the instructions listed in the program debug info.
+/// Generated files which do not exist on disk (e.g. `autogen/starknet/...`) are ignored.
+///
+/// Returns `None` if an input file cannot be found, in which case the program should not be
+/// cached as there is no way to tell when it changes.
+pub fn hash_dependencies(
+	program_json: &Value,
+	path_to_cairo_file: &Path,
+	cairo_path: &[PathBuf],
+) -> Result<Option<HashMap<PathBuf, String>>, CacheError> {
+	let mut dependencies = hash_imports(path_to_cairo_file, cairo_path)?;
+
+	let instruction_locations = program_json["debug_info"]["instruction_locations"]
+		.as_object()
+		.into_iter()
+		.flatten();
+	for (_, location) in instruction_locations {
+		let filename = match location["inst"]["input_file"]["filename"].as_str() {
+			Some(filename) if !filename.starts_with(AUTOGEN_PREFIX) => PathBuf::from(filename),
+			_ => continue,
+		};
+		if dependencies.contains_key(&filename) {
+			continue
+		}
+		if !filename.is_file() {
+			return Ok(None)
+		}
+		let hash = hash_file(&filename)?;
+		dependencies.insert(filename, hash);
+	}
+	Ok(Some(dependencies))
+}
+
+/// Hash every file imported by a cairo file, directly or not.
+///
+/// Modules are searched like the compiler does: in the `cairo_path` directories, then in the
+/// current directory. Unlike the debug info, imports also cover files which emit no instruction
+/// (constants, structs, contract interfaces...).
+/// The files which would shadow an imported module, or provide a module that could not be found
+/// (e.g. the ones of the standard library), are recorded as missing with `MISSING_FILE_HASH`,
+/// so that creating them invalidates the cache.
+pub fn hash_imports(
+	path_to_cairo_file: &Path,
+	cairo_path: &[PathBuf],
+) -> Result<HashMap<PathBuf, String>, CacheError> {
+	let search_dirs: Vec<PathBuf> = cairo_path.iter().cloned().chain([PathBuf::new()]).collect();
+
+	let mut dependencies = HashMap::new();
+	let mut files_to_scan = vec![path_to_cairo_file.to_path_buf()];
+	while let Some(file) = files_to_scan.pop() {
+		let content = read_to_string(&file)
+			.map_err(|e| CacheError::ReadFile(file.display().to_string(), e))?;
+
+		for capture in IMPORT_REGEX.captures_iter(&content) {
+			let module = match capture.get(1).or_else(|| capture.get(2)) {
+				Some(module) => module.as_str(),
+				None => continue,
+			};
+			let module_path = PathBuf::from(module.replace('.', "/")).with_extension("cairo");
+
+			for dir in &search_dirs {
+				let candidate = dir.join(&module_path);
+				match dependencies.get(&candidate) {
+					Some(hash) if hash != MISSING_FILE_HASH => break,
+					Some(_) => continue,
+					None => (),
+				}
+				if candidate.is_file() {
+					let hash = hash_file(&candidate)?;
+					dependencies.insert(candidate.clone(), hash);
+					files_to_scan.push(candidate);
+					break
+				}
+				dependencies.insert(candidate, MISSING_FILE_HASH.to_string());
+			}
+		}
+	}
+	Ok(dependencies)
+}
 
-	let path_hash = hash(path_to_cairo_file);
+/// Hash every source file a compilation error depends on.
+///
+/// Since there is no debug info to rely on, the dependencies are the files imported by the
+/// compiled file, see ``hash_imports``, along with the files in which the compiler located the
+/// errors. This way, a cached error is discarded as soon as an imported file is modified or a
+/// missing module is created.
+///
+/// Returns `None` if a file the compiler located an error in cannot be found.
+pub fn hash_error_dependencies(
+	stderr: &str,
+	path_to_cairo_file: &Path,
+	cairo_path: &[PathBuf],
+) -> Result<Option<HashMap<PathBuf, String>>, CacheError> {
+	let mut dependencies = hash_imports(path_to_cairo_file, cairo_path)?;
+	for capture in ERROR_LOCATION_REGEX.captures_iter(stderr) {
+		let filename = PathBuf::from(&capture[1]);
+		if dependencies.contains_key(&filename) || filename.starts_with(AUTOGEN_PREFIX) {
+			continue
+		}
+		if !filename.is_file() {
+			return Ok(None)
+		}
+		let hash = hash_file(&filename)?;
+		dependencies.insert(filename, hash);
+	}
+	Ok(Some(dependencies))
+}
 
+pub fn get_compile_cache_path(path_to_cairo_file: &PathBuf) -> Result<PathBuf, CacheError> {
 	// Build path to save the  compiled file
 	let mut cache_path = cache_dir()?;
 	cache_path.push(CAIRO_FOUNDRY_CACHE_DIR);
@@ -115,20 +289,53 @@ pub fn get_compile_cache_path(path_to_cairo_file: &PathBuf) -> Result<PathBuf, C
 	fs::create_dir_all(&cache_path)
 		.map_err(|e| CacheError::DirCreation(cache_path.as_path().display().to_string(), e))?;
 
-	cache_path.push(format!("{filename}_{path_hash}"));
+	cache_path.push(cache_file_name(path_to_cairo_file)?);
 	cache_path.set_extension(JSON_FILE_EXTENTION);
 
 	Ok(cache_path)
 }
 
-pub fn hash_file(path: &PathBuf) -> Result<u64, CacheError> {
+/// Returns the directory holding the fuzzing corpora, in the cache directory
+pub fn default_corpus_root() -> Result<PathBuf, CacheError> {
+	Ok(cache_dir()?.join(CAIRO_FOUNDRY_CACHE_DIR).join(CORPUS_DIR))
+}
+
+/// Returns the directory holding the fuzzing corpus of the tests of a cairo file, under the
+/// given root directory
+pub fn get_corpus_dir(
+	corpus_root: &Path,
+	path_to_cairo_file: &PathBuf,
+) -> Result<PathBuf, CacheError> {
+	let corpus_dir = corpus_root.join(cache_file_name(path_to_cairo_file)?);
+
+	fs::create_dir_all(&corpus_dir)
+		.map_err(|e| CacheError::DirCreation(corpus_dir.as_path().display().to_string(), e))?;
+
+	Ok(corpus_dir)
+}
+
+/// Name under which the data of a cairo file is cached: the file stem followed by a hash of
+/// its path, so that files with the same name do not collide
+fn cache_file_name(path_to_cairo_file: &PathBuf) -> Result<String, CacheError> {
+	// Retrieve only the file name to create a clean compiled file name.
+	let filename = path_to_cairo_file.file_stem().and_then(|f| f.to_str()).ok_or_else(|| {
+		CacheError::StemlessFile(path_to_cairo_file.as_path().display().to_string())
+	})?;
+
+	let path_hash = &hash(path_to_cairo_file.to_string_lossy().as_bytes())[..16];
+	Ok(format!("{filename}_{path_hash}"))
+}
+
+/// Returns the hex encoded SHA-256 of a file content
+///
+/// Unlike `DefaultHasher`, SHA-256 is stable across Rust releases, so cache items can be
+/// reused after upgrading the toolchain.
+pub fn hash_file(path: &PathBuf) -> Result<String, CacheError> {
 	let data =
 		std::fs::read(path).map_err(|e| CacheError::ReadFile(path.display().to_string(), e))?;
 	Ok(hash(&data))
 }
 
-fn hash<T: Hash>(t: &T) -> u64 {
-	let mut s = DefaultHasher::new();
-	t.hash(&mut s);
-	s.finish()
+fn hash(data: &[u8]) -> String {
+	format!("{:x}", Sha256::digest(data))
 }
@@ -1,8 +1,20 @@
-use std::fs;
+use std::{collections::HashMap, fs, path::PathBuf};
 
 use assert_matches::assert_matches;
+use serde_json::json;
 
-use super::{CacheError, CompileCacheItem};
+use super::{
+	hash_dependencies, hash_error_dependencies, hash_file, hash_imports, CacheError,
+	CompileCacheItem, CompileCacheKey, CompileOutput, CACHE_FORMAT_VERSION, MISSING_FILE_HASH,
+};
+
+fn cache_key(hash: &str) -> CompileCacheKey {
+	CompileCacheKey {
+		hash: hash.to_string(),
+		compiler_version: String::from("cairo-compile 0.10.0"),
+		compile_args: Vec::new(),
+	}
+}
 
 #[test]
 fn read_cache_with_valid_input() {
@@ -11,8 +23,10 @@ fn read_cache_with_valid_input() {
 	let found_cache = CompileCacheItem::read(&cache_path).unwrap();
 
 	let expected_cache = CompileCacheItem {
-		program_json: "".into(),
-		hash: 10,
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program("".into()),
+		dependencies: HashMap::new(),
 	};
 
 	assert_eq!(found_cache, expected_cache);
@@ -26,6 +40,35 @@ fn read_non_existing_cache_file() {
 	assert_matches!(result, Err(CacheError::ReadFile(_, _)));
 }
 
+#[test]
+fn read_cache_with_legacy_format() {
+	let current_dir = std::env::current_dir().unwrap();
+	let cache_path = current_dir.join("test_cache_files").join("test_legacy_format.json");
+	let result = CompileCacheItem::read(&cache_path);
+	assert_matches!(result, Err(CacheError::UnsupportedVersion(0)));
+}
+
+#[test]
+fn cache_is_invalidated_when_the_key_changes() {
+	let cache = CompileCacheItem {
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program("".into()),
+		dependencies: HashMap::new(),
+	};
+
+	assert!(cache.is_valid_for(&cache_key("10")));
+	assert!(!cache.is_valid_for(&cache_key("20")));
+	assert!(!cache.is_valid_for(&CompileCacheKey {
+		compiler_version: String::from("cairo-compile 0.10.3"),
+		..cache_key("10")
+	}));
+	assert!(!cache.is_valid_for(&CompileCacheKey {
+		compile_args: vec![String::from("--no_debug_info")],
+		..cache_key("10")
+	}));
+}
+
 #[test]
 fn read_existing_cache_with_incorrect_field() {
 	let current_dir = std::env::current_dir().unwrap();
@@ -44,8 +87,10 @@ fn write_cache_with_valid_input() -> Result<(), CacheError> {
 	let cache_path = cache_dir.join("write_cache_with_valid_input.json");
 
 	let expected_cache = CompileCacheItem {
-		program_json: "".into(),
-		hash: 10,
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program("".into()),
+		dependencies: HashMap::new(),
 	};
 
 	CompileCacheItem::write(&expected_cache, &cache_path)?;
@@ -67,8 +112,10 @@ fn update_cache_with_valid_input() -> Result<(), CacheError> {
 	let cache_path = cache_dir.join("update_cache_with_valid_input.json");
 
 	let expected_cache = CompileCacheItem {
-		program_json: "".into(),
-		hash: 10,
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program("".into()),
+		dependencies: HashMap::new(),
 	};
 
 	CompileCacheItem::write(&expected_cache, &cache_path)?;
@@ -78,8 +125,147 @@ fn update_cache_with_valid_input() -> Result<(), CacheError> {
 	assert_eq!(found_cache, expected_cache);
 
 	let expected_cache = CompileCacheItem {
-		program_json: "updated".into(),
-		hash: 20,
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("20"),
+		output: CompileOutput::Program("updated".into()),
+		dependencies: HashMap::new(),
+	};
+
+	CompileCacheItem::write(&expected_cache, &cache_path)?;
+
+	let found_cache = CompileCacheItem::read(&cache_path)?;
+
+	assert_eq!(found_cache, expected_cache);
+
+	Ok(())
+}
+
+#[test]
+fn cache_is_invalidated_when_a_dependency_changes() -> Result<(), CacheError> {
+	let cache_dir = std::env::temp_dir().join("cairo_foundry_test");
+
+	fs::create_dir_all(&cache_dir)
+		.map_err(|e| CacheError::DirCreation(cache_dir.as_path().display().to_string(), e))?;
+
+	let dependency_path = cache_dir.join("cache_dependency.cairo");
+	fs::write(&dependency_path, "func foo() {\n    return ();\n}\n").unwrap();
+	let test_path = cache_dir.join("test_cache_dependency.cairo");
+	fs::write(&test_path, "func test_foo() {\n    return ();\n}\n").unwrap();
+
+	let program_json = json!({
+		"debug_info": {
+			"instruction_locations": {
+				"0": { "inst": { "input_file": { "filename": dependency_path } } },
+				"1": { "inst": { "input_file": { "filename": "autogen/not_on_disk.cairo" } } },
+			}
+		}
+	});
+
+	let cache = CompileCacheItem {
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program(program_json.clone()),
+		dependencies: hash_dependencies(&program_json, &test_path, &[])?.unwrap(),
+	};
+
+	assert_eq!(
+		HashMap::from([(dependency_path.clone(), hash_file(&dependency_path)?)]),
+		cache.dependencies
+	);
+	assert!(cache.dependencies_are_unchanged());
+
+	fs::write(&dependency_path, "func bar() {\n    return ();\n}\n").unwrap();
+
+	assert!(!cache.dependencies_are_unchanged());
+
+	Ok(())
+}
+
+#[test]
+fn unresolved_input_files_are_not_cached() -> Result<(), CacheError> {
+	let cache_dir = std::env::temp_dir().join("cairo_foundry_test");
+
+	fs::create_dir_all(&cache_dir)
+		.map_err(|e| CacheError::DirCreation(cache_dir.as_path().display().to_string(), e))?;
+
+	let test_path = cache_dir.join("test_unresolved_input_file.cairo");
+	fs::write(&test_path, "func test_foo() {\n    return ();\n}\n").unwrap();
+	let program_json = json!({
+		"debug_info": {
+			"instruction_locations": {
+				"0": { "inst": { "input_file": { "filename": "lib/not_on_disk.cairo" } } },
+			}
+		}
+	});
+
+	assert_eq!(None, hash_dependencies(&program_json, &test_path, &[])?);
+
+	Ok(())
+}
+
+#[test]
+fn imports_are_dependencies() -> Result<(), CacheError> {
+	let root = std::env::temp_dir().join("cairo_foundry_test").join("imports_are_dependencies");
+	let lib_dir = root.join("lib");
+	let _ = fs::remove_dir_all(&root);
+	fs::create_dir_all(lib_dir.join("constants"))
+		.map_err(|e| CacheError::DirCreation(lib_dir.as_path().display().to_string(), e))?;
+
+	// Imported files emitting no instruction are dependencies as well
+	let constants_path = lib_dir.join("constants").join("values.cairo");
+	fs::write(&constants_path, "const ANSWER = 42;\n").unwrap();
+	let test_path = root.join("test_imports.cairo");
+	fs::write(
+		&test_path,
+		"from constants.values import ANSWER\nfrom missing.module import (\n    foo,\n)\n",
+	)
+	.unwrap();
+
+	let dependencies = hash_imports(&test_path, &[lib_dir.clone()])?;
+	assert_eq!(
+		Some(&hash_file(&constants_path)?),
+		dependencies.get(&constants_path)
+	);
+	let missing_path = lib_dir.join("missing").join("module.cairo");
+	assert_eq!(
+		Some(&String::from(MISSING_FILE_HASH)),
+		dependencies.get(&missing_path)
+	);
+
+	let cache = CompileCacheItem {
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Program("".into()),
+		dependencies,
+	};
+	assert!(cache.dependencies_are_unchanged());
+
+	// Creating the missing module changes the output of the compiler
+	fs::create_dir_all(lib_dir.join("missing")).unwrap();
+	fs::write(&missing_path, "func foo() {\n    return ();\n}\n").unwrap();
+	assert!(!cache.dependencies_are_unchanged());
+
+	Ok(())
+}
+
+#[test]
+fn write_cache_with_compile_error() -> Result<(), CacheError> {
+	let cache_dir = std::env::temp_dir().join("cairo_foundry_test");
+
+	fs::create_dir_all(&cache_dir)
+		.map_err(|e| CacheError::DirCreation(cache_dir.as_path().display().to_string(), e))?;
+
+	let cache_path = cache_dir.join("write_cache_with_compile_error.json");
+
+	let expected_output = CompileOutput::Error {
+		stderr: String::from("test_foo.cairo:1:1: Unexpected token"),
+		status: 1,
+	};
+	let expected_cache = CompileCacheItem {
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: expected_output.clone(),
+		dependencies: HashMap::new(),
 	};
 
 	CompileCacheItem::write(&expected_cache, &cache_path)?;
@@ -87,6 +273,62 @@ fn update_cache_with_valid_input() -> Result<(), CacheError> {
 	let found_cache = CompileCacheItem::read(&cache_path)?;
 
 	assert_eq!(found_cache, expected_cache);
+	assert_eq!(found_cache.output, expected_output);
+
+	Ok(())
+}
+
+#[test]
+fn compile_error_depends_on_the_files_containing_the_errors() -> Result<(), CacheError> {
+	let stderr = "test_cairo_contracts/test_invalid_program.cairo:2:1: Unsupported directive\n\
+	              %lang starknet\n\
+	              ^************^\n\
+	              not_on_disk.cairo:3:4: Unknown identifier";
+
+	let path = PathBuf::from("test_cairo_contracts/test_invalid_program.cairo");
+
+	// The error located in a file which cannot be found is not cached
+	assert_eq!(None, hash_error_dependencies(stderr, &path, &[])?);
+
+	let stderr = stderr.split("\nnot_on_disk").next().unwrap();
+	let dependencies = hash_error_dependencies(stderr, &path, &[])?.unwrap();
+	assert_eq!(Some(&hash_file(&path)?), dependencies.get(&path));
+
+	Ok(())
+}
+
+#[test]
+fn compile_error_depends_on_the_imports() -> Result<(), CacheError> {
+	let root = std::env::temp_dir()
+		.join("cairo_foundry_test")
+		.join("compile_error_depends_on_the_imports");
+	let _ = fs::remove_dir_all(&root);
+	fs::create_dir_all(&root)
+		.map_err(|e| CacheError::DirCreation(root.as_path().display().to_string(), e))?;
+
+	// "could not find module" errors do not name the file the module is searched in
+	let test_path = root.join("test_missing_import.cairo");
+	fs::write(&test_path, "from lib.utils import foo\n").unwrap();
+	let stderr = "Could not find module 'lib.utils'.";
+
+	let cache = CompileCacheItem {
+		version: CACHE_FORMAT_VERSION,
+		key: cache_key("10"),
+		output: CompileOutput::Error {
+			stderr: stderr.to_string(),
+			status: 1,
+		},
+		dependencies: hash_error_dependencies(stderr, &test_path, &[root.clone()])?.unwrap(),
+	};
+	assert!(cache.dependencies_are_unchanged());
+
+	fs::create_dir_all(root.join("lib")).unwrap();
+	fs::write(
+		root.join("lib").join("utils.cairo"),
+		"func foo() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	assert!(!cache.dependencies_are_unchanged());
 
 	Ok(())
 }
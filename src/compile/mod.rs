@@ -1,26 +1,56 @@
 use cairo_rs::serde::deserialize_program::ProgramJson;
-use log::warn;
+use lazy_static::lazy_static;
+use log::{info, warn};
 use serde_json::Value;
-use std::{fmt::Debug, io, path::PathBuf, process::Command};
+use std::{
+	collections::HashMap,
+	fmt::Debug,
+	fs::read_to_string,
+	io,
+	path::PathBuf,
+	process::{Command, Output},
+	sync::Mutex,
+};
 use thiserror::Error;
 use which::{which, Error as WhichError};
 
-use self::cache::{get_compile_cache_path, hash_file, CacheError, CompileCacheItem};
+use self::cache::{
+	get_compile_cache_path, hash_dependencies, hash_error_dependencies, hash_file, CacheError,
+	CompileCacheItem, CompileCacheKey, CompileOutput, CACHE_FORMAT_VERSION,
+};
 
 pub mod cache;
+#[cfg(test)]
+mod tests;
 
 const CAIRO_COMPILE_BINARY: &str = "cairo-compile";
+const STARKNET_COMPILE_BINARY: &str = "starknet-compile";
+
+/// Directive declaring a file as a Starknet contract
+const STARKNET_LANG_DIRECTIVE: &str = "%lang starknet";
+
+lazy_static! {
+	/// Output of `<binary> --version` for each compiler binary used so far, so that the
+	/// compiler is only asked for its version once per process
+	static ref COMPILER_VERSIONS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("binary '{CAIRO_COMPILE_BINARY}' not found: {0}")]
+	#[error("compiler binary not found: {0}")]
 	CairoCompileBinaryNotFound(#[from] WhichError),
 	#[error("failed to execute a process: {0}")]
 	RunProcess(io::Error),
 	#[error("binary '{0}' failed to compile '{1}'")]
 	Compilation(String, String),
+	#[error("binary '{0}' exited with status {1}: {2}")]
+	CompilationFailed(String, i32, String),
 	#[error("file '{0}' has no stem")]
 	StemlessFile(String),
+	#[error("failed to read file '{0}': {1}")]
+	ReadFile(String, io::Error),
+	#[error("file '{0}' is not a Starknet contract")]
+	NotAStarknetContract(String),
 	#[error("cache directory does not exist on this platform")]
 	CacheDirSupported,
 	#[error("failed to create file '{0}': {1}")]
@@ -35,39 +65,173 @@ pub enum Error {
 	CacheError(#[from] CacheError),
 }
 
+/// Options forwarded to the compiler
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompileOptions {
+	/// Directories searched for imported modules
+	pub cairo_path: Vec<PathBuf>,
+	/// Add the `__start__` and `__end__` labels a program needs to be run in proof mode
+	pub proof_mode: bool,
+}
+
+impl CompileOptions {
+	/// Returns the compiler arguments corresponding to these options
+	pub fn args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		if !self.cairo_path.is_empty() {
+			let cairo_path = self
+				.cairo_path
+				.iter()
+				.map(|dir| dir.display().to_string())
+				.collect::<Vec<_>>()
+				.join(":");
+			args.push(String::from("--cairo_path"));
+			args.push(cairo_path);
+		}
+		if self.proof_mode {
+			args.push(String::from("--proof_mode"));
+		}
+		args
+	}
+}
+
+/// Compiler used for a cairo file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compiler {
+	Cairo,
+	Starknet,
+}
+
+impl Compiler {
+	/// Select the compiler of a cairo file, `starknet-compile` for files declared with
+	/// `%lang starknet` and `cairo-compile` for the others
+	pub fn for_file(path_to_cairo_file: &PathBuf) -> Result<Self, Error> {
+		let content = read_to_string(path_to_cairo_file)
+			.map_err(|e| Error::ReadFile(path_to_cairo_file.as_path().display().to_string(), e))?;
+
+		if content.lines().any(|line| line.trim() == STARKNET_LANG_DIRECTIVE) {
+			Ok(Self::Starknet)
+		} else {
+			Ok(Self::Cairo)
+		}
+	}
+
+	pub fn binary(&self) -> &'static str {
+		match self {
+			Self::Cairo => CAIRO_COMPILE_BINARY,
+			Self::Starknet => STARKNET_COMPILE_BINARY,
+		}
+	}
+
+	/// Returns the compiler arguments, the given options included
+	pub fn args(&self, options: &CompileOptions) -> Vec<String> {
+		let mut args = options.args();
+		if *self == Self::Starknet {
+			// Test files rely on our own hints, which are not whitelisted by Starknet
+			args.push(String::from("--disable_hint_validation"));
+		}
+		args
+	}
+}
+
+/// A compiled Starknet contract
+#[derive(Debug, Clone)]
+pub struct CompiledContract {
+	pub program_json: ProgramJson,
+	pub abi: Value,
+	/// Selectors and offsets of the `EXTERNAL`, `L1_HANDLER` and `CONSTRUCTOR` entry points
+	pub entry_points_by_type: Value,
+}
+
 /// Compile a cairo file.
 ///
 /// The given `&PathBuf` will be compiled as a simple Cairo file which can then be executed.
 /// The generated file will be a JSON file saved in your cache directory.
+/// Starknet contracts are compiled with `starknet-compile`, and only their program is returned.
 ///
 /// Returns the `PathBuf` to the generated file and print the displayable element from the given
 /// `PathBuf' or nothing, which means that everything is fine.
 ///
+/// The cached program is reused as long as neither the given file nor any of the files it
+/// imports changed, and as long as it was compiled by the same compiler version with the same
+/// arguments.
+/// Compilation errors are cached as well, so that unchanged broken files are reported
+/// without invoking the compiler again.
+///
 /// # Examples
 ///
 /// Basic usage:
 ///
 /// ```no_run
 /// # use std::path::PathBuf;
-/// # use cairo_foundry::compile::{compile, Error};
+/// # use cairo_foundry::compile::{compile, CompileOptions, Error};
 /// # fn foo() -> Result<(), Error> {
 ///     let mut program = PathBuf::from("path_to_your_program");
-///     let compiled_program_path = compile(&program)?;
+///     let compiled_program_path = compile(&program, &CompileOptions::default())?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn compile(path_to_cairo_file: &PathBuf) -> Result<ProgramJson, Error> {
+pub fn compile(
+	path_to_cairo_file: &PathBuf,
+	options: &CompileOptions,
+) -> Result<ProgramJson, Error> {
+	let compiler = Compiler::for_file(path_to_cairo_file)?;
+	let mut output = compile_with_cache(path_to_cairo_file, compiler, options)?;
+
+	let program_json = match compiler {
+		Compiler::Cairo => output,
+		Compiler::Starknet => output["program"].take(),
+	};
+	Ok(serde_json::from_value(program_json)?)
+}
+
+/// Compile a Starknet contract, declared with `%lang starknet`.
+///
+/// Like `compile`, the compiled contract is cached.
+///
+/// Returns the program of the contract along with its ABI and entry points.
+pub fn compile_contract(
+	path_to_cairo_file: &PathBuf,
+	options: &CompileOptions,
+) -> Result<CompiledContract, Error> {
+	if Compiler::for_file(path_to_cairo_file)? != Compiler::Starknet {
+		return Err(Error::NotAStarknetContract(
+			path_to_cairo_file.as_path().display().to_string(),
+		))
+	}
+	let mut output = compile_with_cache(path_to_cairo_file, Compiler::Starknet, options)?;
+
+	Ok(CompiledContract {
+		program_json: serde_json::from_value(output["program"].take())?,
+		abi: output["abi"].take(),
+		entry_points_by_type: output["entry_points_by_type"].take(),
+	})
+}
+
+/// Returns the output of the compiler for the given file, from the cache if possible
+fn compile_with_cache(
+	path_to_cairo_file: &PathBuf,
+	compiler: Compiler,
+	options: &CompileOptions,
+) -> Result<Value, Error> {
 	let cache_path = get_compile_cache_path(path_to_cairo_file)?;
 
-	let hash = hash_file(path_to_cairo_file)?;
+	let key = CompileCacheKey {
+		hash: hash_file(path_to_cairo_file)?,
+		compiler_version: compiler_version(compiler.binary())?,
+		compile_args: compiler.args(options),
+	};
 
 	if cache_path.exists() {
 		match CompileCacheItem::read(&cache_path) {
 			Ok(cache) =>
-				if cache.hash == hash {
-					let program_json: ProgramJson = serde_json::from_value(cache.program_json)?;
-					return Ok(program_json)
+				if cache.is_valid_for(&key) {
+					return into_result(cache.output, compiler)
 				},
+			Err(CacheError::UnsupportedVersion(version)) => info!(
+				"Discarding cache {} written with format version {version}",
+				cache_path.display().to_string()
+			),
 			Err(err) => warn!(
 				"Error while reading cache {}: {err}",
 				cache_path.display().to_string()
@@ -75,43 +239,105 @@ pub fn compile(path_to_cairo_file: &PathBuf) -> Result<ProgramJson, Error> {
 		}
 	}
 
-	let compile_output = compile_cairo_file(path_to_cairo_file)?;
-
-	let program_json: Value = serde_json::from_slice(&compile_output)?;
+	let process_output = compile_cairo_file(path_to_cairo_file, compiler, &key.compile_args)?;
 
-	let cache = CompileCacheItem {
-		program_json: program_json.clone(),
-		hash,
+	let (output, dependencies) = match process_output.status.code() {
+		Some(0) => {
+			let compiled: Value = serde_json::from_slice(&process_output.stdout)?;
+			let program = match compiler {
+				Compiler::Cairo => &compiled,
+				Compiler::Starknet => &compiled["program"],
+			};
+			let dependencies = hash_dependencies(program, path_to_cairo_file, &options.cairo_path)?;
+			(CompileOutput::Program(compiled), dependencies)
+		},
+		Some(status) => {
+			let stderr = String::from_utf8(process_output.stderr).unwrap_or_else(|e| {
+				format!(
+					"{} with non utf8 error message: {}",
+					path_to_cairo_file.as_path().display(),
+					e
+				)
+			});
+			let dependencies =
+				hash_error_dependencies(&stderr, path_to_cairo_file, &options.cairo_path)?;
+			(CompileOutput::Error { stderr, status }, dependencies)
+		},
+		// Terminated by a signal, there is no point in caching this failure
+		None =>
+			return Err(Error::Compilation(
+				compiler.binary().to_string(),
+				String::from_utf8_lossy(&process_output.stderr).to_string(),
+			)),
 	};
 
-	let program_json: ProgramJson = serde_json::from_value(program_json)?;
+	match dependencies {
+		Some(dependencies) => {
+			let cache = CompileCacheItem {
+				version: CACHE_FORMAT_VERSION,
+				key,
+				output: output.clone(),
+				dependencies,
+			};
+			cache.write(&cache_path)?;
+		},
+		None => info!(
+			"Not caching {}: some of the files it depends on could not be found",
+			path_to_cairo_file.display()
+		),
+	}
 
-	cache.write(&cache_path)?;
+	into_result(output, compiler)
+}
 
-	Ok(program_json)
+fn into_result(output: CompileOutput, compiler: Compiler) -> Result<Value, Error> {
+	match output {
+		CompileOutput::Program(compiled) => Ok(compiled),
+		CompileOutput::Error { stderr, status } => Err(Error::CompilationFailed(
+			compiler.binary().to_string(),
+			status,
+			stderr,
+		)),
+	}
 }
 
-pub fn compile_cairo_file(path_to_cairo_file: &PathBuf) -> Result<Vec<u8>, Error> {
-	let path_to_cairo_compiler = which(CAIRO_COMPILE_BINARY)?;
+/// Returns the version of the given compiler binary, as printed by `<binary> --version`
+pub fn compiler_version(binary: &str) -> Result<String, Error> {
+	if let Some(version) = COMPILER_VERSIONS.lock().unwrap().get(binary) {
+		return Ok(version.clone())
+	}
 
-	// Use cairo-compile binary in order to compile the .cairo file
-	let compilation_output = Command::new(CAIRO_COMPILE_BINARY)
-		.args([&path_to_cairo_file])
+	let path_to_compiler = which(binary)?;
+	let output = Command::new(&path_to_compiler)
+		.arg("--version")
 		.output()
 		.map_err(Error::RunProcess)?;
-
-	if compilation_output.status.success() {
-		Ok(compilation_output.stdout)
-	} else {
+	if !output.status.success() {
 		return Err(Error::Compilation(
-			path_to_cairo_compiler.as_path().display().to_string(),
-			String::from_utf8(compilation_output.stderr).unwrap_or_else(|e| {
-				format!(
-					"{} with non utf8 error message: {}",
-					path_to_cairo_file.as_path().display(),
-					e
-				)
-			}),
+			path_to_compiler.as_path().display().to_string(),
+			String::from_utf8_lossy(&output.stderr).to_string(),
 		))
 	}
+
+	let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+	COMPILER_VERSIONS.lock().unwrap().insert(binary.to_string(), version.clone());
+	Ok(version)
+}
+
+/// Run the compiler on a cairo file
+///
+/// Returns the output of the compiler process, whether the compilation succeeded or not.
+pub fn compile_cairo_file(
+	path_to_cairo_file: &PathBuf,
+	compiler: Compiler,
+	compile_args: &[String],
+) -> Result<Output, Error> {
+	which(compiler.binary())?;
+
+	// Use cairo-compile (or starknet-compile) binary in order to compile the .cairo file
+	Command::new(compiler.binary())
+		.arg(path_to_cairo_file)
+		.args(compile_args)
+		.output()
+		.map_err(Error::RunProcess)
 }
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use assert_matches::assert_matches;
+
+use super::{compile, compile_contract, CompileOptions, Compiler, Error};
+
+#[test]
+fn compile_options_args() {
+	assert!(CompileOptions::default().args().is_empty());
+
+	let options = CompileOptions {
+		cairo_path: vec![PathBuf::from("src"), PathBuf::from("lib/openzeppelin")],
+		proof_mode: true,
+	};
+	assert_eq!(
+		vec!["--cairo_path", "src:lib/openzeppelin", "--proof_mode"],
+		options.args()
+	);
+}
+
+#[test]
+fn select_compiler_from_lang_directive() -> Result<(), Error> {
+	assert_eq!(
+		Compiler::Starknet,
+		Compiler::for_file(&PathBuf::from(
+			"test_cairo_contracts/test_invalid_program.cairo"
+		))?
+	);
+	assert_eq!(
+		Compiler::Cairo,
+		Compiler::for_file(&PathBuf::from(
+			"test_cairo_contracts/test_valid_program.cairo"
+		))?
+	);
+	Ok(())
+}
+
+#[test]
+fn compile_error_reports_exit_status() {
+	let dir = std::env::temp_dir().join("cairo_foundry_test");
+	std::fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("compile_error_reports_exit_status.cairo");
+	std::fs::write(&path, "func main( {\n").unwrap();
+
+	// The second compilation is served from the cache
+	for _ in 0..2 {
+		assert_matches!(
+			compile(&path, &CompileOptions::default()),
+			Err(Error::CompilationFailed(_, status, _)) if status != 0
+		);
+	}
+}
+
+#[test]
+fn compile_contract_extracts_abi_and_entry_points() -> Result<(), Error> {
+	let contract = compile_contract(
+		&PathBuf::from("test_cairo_contracts/test_invalid_program.cairo"),
+		&CompileOptions::default(),
+	)?;
+
+	let abi_names: Vec<_> = contract
+		.abi
+		.as_array()
+		.unwrap()
+		.iter()
+		.filter_map(|entry| entry["name"].as_str())
+		.collect();
+	assert!(abi_names.contains(&"increase_balance"), "{abi_names:?}");
+	assert!(abi_names.contains(&"get_balance"), "{abi_names:?}");
+	assert_eq!(
+		2,
+		contract.entry_points_by_type["EXTERNAL"].as_array().unwrap().len()
+	);
+	Ok(())
+}
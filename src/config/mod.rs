@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fs::read_to_string,
+	io,
+	path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const CONFIG_FILE_NAME: &str = "cairo-foundry.toml";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+	#[error("failed to read config file '{0}': {1}")]
+	ReadFile(PathBuf, io::Error),
+	#[error("invalid config file '{0}': {1}")]
+	Deserialize(PathBuf, toml::de::Error),
+}
+
+/// Project configuration, read from the `cairo-foundry.toml` file found at the root of the
+/// tested directory
+///
+/// Command line options take precedence over (or are added to) the values of this file.
+///
+/// # Example
+///
+/// ```toml
+/// # Directories searched for imported modules, relative to the config file
+/// cairo_path = ["src", "lib/cairo-contracts/src"]
+/// # Layout the tests are run with
+/// layout = "all"
+/// ```
+#[derive(Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	/// Directories searched for imported modules
+	pub cairo_path: Vec<PathBuf>,
+	/// Layout the tests are run with, unless overridden by the command line
+	pub layout: Option<String>,
+}
+
+impl Config {
+	/// Load the config file located in the given directory.
+	///
+	/// Returns the default config if there is no such file.
+	/// Relative paths are resolved against the given directory.
+	pub fn load(root: &Path) -> Result<Self, ConfigError> {
+		let path = root.join(CONFIG_FILE_NAME);
+		if !path.exists() {
+			return Ok(Default::default())
+		}
+
+		let content = read_to_string(&path).map_err(|e| ConfigError::ReadFile(path.clone(), e))?;
+		let mut config: Config =
+			toml::from_str(&content).map_err(|e| ConfigError::Deserialize(path, e))?;
+
+		config.cairo_path = config.cairo_path.into_iter().map(|dir| root.join(dir)).collect();
+
+		Ok(config)
+	}
+}
@@ -0,0 +1,53 @@
+use std::fs;
+
+use assert_matches::assert_matches;
+
+use super::*;
+
+fn config_dir(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join("cairo_foundry_test").join(name);
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+#[test]
+fn load_default_config_when_file_is_missing() {
+	let config = Config::load(&PathBuf::from("./test_cairo_contracts"));
+
+	assert_matches!(config, Ok(config) if config == Config::default());
+}
+
+#[test]
+fn load_config_with_paths_relative_to_root() {
+	let root = config_dir("load_config_with_paths_relative_to_root");
+	fs::write(
+		root.join(CONFIG_FILE_NAME),
+		"cairo_path = [\"src\", \"/abs/lib\"]\n",
+	)
+	.unwrap();
+
+	let config = Config::load(&root).unwrap();
+
+	assert_eq!(
+		vec![root.join("src"), PathBuf::from("/abs/lib")],
+		config.cairo_path
+	);
+}
+
+#[test]
+fn load_config_with_layout() {
+	let root = config_dir("load_config_with_layout");
+	fs::write(root.join(CONFIG_FILE_NAME), "layout = \"all\"\n").unwrap();
+
+	let config = Config::load(&root).unwrap();
+
+	assert_eq!(Some(String::from("all")), config.layout);
+}
+
+#[test]
+fn load_config_with_unknown_field() {
+	let root = config_dir("load_config_with_unknown_field");
+	fs::write(root.join(CONFIG_FILE_NAME), "unknown = 1\n").unwrap();
+
+	assert_matches!(Config::load(&root), Err(ConfigError::Deserialize(_, _)));
+}
@@ -0,0 +1,73 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+use num_bigint::BigInt;
+
+use crate::compile::cache::{get_corpus_dir, CacheError, JSON_FILE_EXTENTION};
+
+/// Failing inputs of a fuzzed test, replayed before any random input on later runs
+///
+/// Inputs are kept even once the test passes with them, so that a fixed bug stays covered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corpus {
+	path: PathBuf,
+	inputs: Vec<Vec<BigInt>>,
+}
+
+impl Corpus {
+	/// Open the corpus of a test of the given cairo file, stored under `corpus_root` (see
+	/// ``default_corpus_root``)
+	pub fn open(
+		corpus_root: &Path,
+		path_to_cairo_file: &PathBuf,
+		test_name: &str,
+	) -> Result<Self, CacheError> {
+		let mut path = get_corpus_dir(corpus_root, path_to_cairo_file)?;
+		path.push(test_name);
+		path.set_extension(JSON_FILE_EXTENTION);
+		Ok(Self::open_file(path))
+	}
+
+	/// Open a corpus file, empty if it does not exist yet
+	///
+	/// Like outdated compile cache items, a corpus which cannot be read is discarded.
+	pub fn open_file(path: PathBuf) -> Self {
+		let inputs = fs::read_to_string(&path)
+			.ok()
+			.and_then(|content| serde_json::from_str::<Vec<Vec<String>>>(&content).ok())
+			.map(|inputs| {
+				inputs
+					.iter()
+					.filter_map(|input| {
+						input.iter().map(|felt| BigInt::from_str(felt).ok()).collect()
+					})
+					.collect()
+			})
+			.unwrap_or_default();
+		Self { path, inputs }
+	}
+
+	pub fn inputs(&self) -> &[Vec<BigInt>] {
+		&self.inputs
+	}
+
+	/// Add a failing input to the corpus and write it to its file
+	pub fn add(&mut self, input: Vec<BigInt>) -> Result<(), CacheError> {
+		if self.inputs.contains(&input) {
+			return Ok(())
+		}
+		self.inputs.push(input);
+
+		let content: Vec<Vec<String>> = self
+			.inputs
+			.iter()
+			.map(|input| input.iter().map(BigInt::to_string).collect())
+			.collect();
+		let content = serde_json::to_string(&content).map_err(CacheError::SerializeError)?;
+		fs::write(&self.path, content)
+			.map_err(|e| CacheError::WriteToFile(self.path.display().to_string(), e))
+	}
+}
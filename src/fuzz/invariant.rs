@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use cairo_rs::{types::exec_scope::ExecutionScopes, vm::errors::vm_errors::VirtualMachineError};
+use num_bigint::BigInt;
+use serde_json::Value;
+
+use super::{shrink, FeltGenerator};
+use crate::{
+	cairo_run::{COMPILE_OPTIONS_VAR_NAME, LAYOUT_VAR_NAME},
+	compile::CompileOptions,
+	hints::{
+		contracts::{call_contract, declare_contract, deploy_contract},
+		sn_keccak,
+		syscalls::{get_starknet_state, StarknetState, STARKNET_STATE_VAR_NAME},
+	},
+	hooks::{setup_hooks, HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+};
+
+/// An external function of the target contract, called with fuzzed felts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetFunction {
+	pub name: String,
+	pub selector: BigInt,
+	/// Names of the arguments of the function
+	pub inputs: Vec<String>,
+}
+
+/// Context the target contract of an invariant test is deployed and called in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallContext {
+	/// Layout the calls to the contract are run with
+	pub layout: String,
+	/// Options the contract is compiled with
+	pub compile_options: CompileOptions,
+	/// Number of steps each call to the contract may take
+	pub max_steps: u64,
+}
+
+/// The contract whose external functions are called by an invariant test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetContract {
+	pub address: BigInt,
+	pub functions: Vec<TargetFunction>,
+	pub context: CallContext,
+}
+
+/// A call to a function of the target contract, given by its index in `functions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+	pub function: usize,
+	pub calldata: Vec<BigInt>,
+}
+
+impl TargetContract {
+	/// Declare and deploy the target contract of an invariant test in the given state
+	pub fn deploy(
+		state: &mut StarknetState,
+		path: &Path,
+		constructor_calldata: &[BigInt],
+		context: CallContext,
+	) -> Result<Self, VirtualMachineError> {
+		let (class_hash, address) = with_exec_scopes(state, &context, |exec_scopes| {
+			let class_hash = declare_contract(exec_scopes, path, &context.compile_options)?;
+			let address = deploy_contract(exec_scopes, 0, &class_hash, constructor_calldata)?;
+			Ok((class_hash, address))
+		})?;
+		let abi = &state.classes[&class_hash].abi;
+
+		Ok(Self {
+			address,
+			functions: target_functions(abi),
+			context,
+		})
+	}
+
+	/// Returns a call to a random function of the contract, with random felts as arguments
+	pub fn random_call(&self, generator: &mut FeltGenerator) -> Call {
+		let function = generator.index(self.functions.len());
+		Call {
+			function,
+			calldata: generator.felts(self.functions[function].inputs.len()),
+		}
+	}
+
+	/// Make a call to the contract from the test contract
+	///
+	/// A call which fails, like one running out of steps, is reverted: the state is left as it
+	/// was before the call.
+	pub fn call(&self, state: &mut StarknetState, call: &Call) -> Result<(), VirtualMachineError> {
+		let snapshot = state.clone();
+		let selector = &self.functions[call.function].selector;
+		let result = with_exec_scopes(state, &self.context, |exec_scopes| {
+			call_contract(exec_scopes, 0, &self.address, selector, &call.calldata)
+		});
+		if result.is_err() {
+			*state = snapshot;
+		}
+		result.map(|_| ())
+	}
+
+	/// Format a call as `name(argument=value, ...)`
+	pub fn format_call(&self, call: &Call) -> String {
+		let function = &self.functions[call.function];
+		let args = function
+			.inputs
+			.iter()
+			.zip(&call.calldata)
+			.map(|(name, felt)| format!("{name}={felt}"))
+			.collect::<Vec<_>>()
+			.join(", ");
+		format!("{}({})", function.name, args)
+	}
+}
+
+/// Returns the external functions of an ABI which change the state and only take felts
+///
+/// `@view` functions are left out as they cannot break an invariant.
+pub fn target_functions(abi: &Value) -> Vec<TargetFunction> {
+	abi.as_array()
+		.into_iter()
+		.flatten()
+		.filter(|entry| entry["type"] == "function" && entry["stateMutability"] != "view")
+		.filter_map(|entry| {
+			let name = entry["name"].as_str()?;
+			let inputs = entry["inputs"]
+				.as_array()?
+				.iter()
+				.map(
+					|input| match (input["name"].as_str(), input["type"].as_str()) {
+						(Some(name), Some("felt")) => Some(name.to_string()),
+						_ => None,
+					},
+				)
+				.collect::<Option<_>>()?;
+			Some(TargetFunction {
+				name: name.to_string(),
+				selector: sn_keccak(name.as_bytes()),
+				inputs,
+			})
+		})
+		.collect()
+}
+
+/// Shrink a failing sequence of calls: calls are removed one at a time, then the arguments of
+/// the remaining calls are shrunk like the inputs of a fuzzed test.
+///
+/// `fails` tells whether the invariant is still broken by a candidate sequence.
+pub fn shrink_sequence(
+	sequence: &[Call],
+	prime: &BigInt,
+	mut fails: impl FnMut(&[Call]) -> bool,
+) -> Vec<Call> {
+	let mut sequence = sequence.to_vec();
+
+	let mut i = 0;
+	while i < sequence.len() {
+		let mut candidate = sequence.clone();
+		candidate.remove(i);
+		if fails(&candidate) {
+			sequence = candidate;
+		} else {
+			i += 1;
+		}
+	}
+
+	for i in 0..sequence.len() {
+		let calldata = shrink(&sequence[i].calldata, prime, |calldata| {
+			let mut candidate = sequence.clone();
+			candidate[i].calldata = calldata.to_vec();
+			fails(&candidate)
+		});
+		sequence[i].calldata = calldata;
+	}
+	sequence
+}
+
+/// Run `f` with execution scopes holding the given state and context, as hints do
+///
+/// The `Hooks` are set so that the entry points called by `f` stop after `context.max_steps`.
+fn with_exec_scopes<T>(
+	state: &mut StarknetState,
+	context: &CallContext,
+	f: impl FnOnce(&mut ExecutionScopes) -> Result<T, VirtualMachineError>,
+) -> Result<T, VirtualMachineError> {
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(STARKNET_STATE_VAR_NAME, std::mem::take(state));
+	exec_scopes.insert_value(LAYOUT_VAR_NAME, context.layout.clone());
+	exec_scopes.insert_value(COMPILE_OPTIONS_VAR_NAME, context.compile_options.clone());
+	exec_scopes.insert_value(HOOKS_VAR_NAME, setup_hooks());
+	exec_scopes.insert_value(MAX_STEPS_VAR_NAME, context.max_steps);
+	let result = f(&mut exec_scopes);
+	*state = std::mem::take(get_starknet_state(&mut exec_scopes)?);
+	result
+}
@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod tests;
+
+mod corpus;
+pub use corpus::Corpus;
+
+mod invariant;
+pub use invariant::*;
+
+use num_bigint::{BigInt, Sign};
+use num_traits::Signed;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Number of inputs a test taking arguments is run with, unless configured otherwise
+pub const DEFAULT_FUZZ_RUNS: usize = 100;
+
+/// Number of calls made to the target contract of an invariant test in each run, unless
+/// configured otherwise
+pub const DEFAULT_INVARIANT_DEPTH: usize = 20;
+
+/// Maximum number of executions spent shrinking a failing input
+const MAX_SHRINK_RUNS: usize = 1000;
+
+/// Maximum number of inputs discarded by `assume` or `fuzz_range` per run, before a fuzzed test
+/// is considered unable to find valid inputs
+pub const MAX_REJECTS_PER_RUN: usize = 10;
+
+/// Inclusive bounds of the felts generated for an argument
+pub type FuzzRange = (BigInt, BigInt);
+
+/// Configuration of the fuzzing of the tests taking explicit arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzConfig {
+	/// Number of inputs each test is run with
+	pub runs: usize,
+	/// Seed of the inputs, so that a failure can be reproduced
+	pub seed: u64,
+	/// Number of calls made to the target contract in each run of an invariant test
+	pub depth: usize,
+}
+
+/// Seeded generator of random felts
+///
+/// Felts close to the bounds of the field or of common integer types break code far more
+/// often than uniformly distributed ones, so they are generated more often.
+pub struct FeltGenerator {
+	rng: StdRng,
+	prime: BigInt,
+	edge_cases: Vec<BigInt>,
+}
+
+impl FeltGenerator {
+	pub fn new(seed: u64, prime: BigInt) -> Self {
+		let edge_cases = vec![
+			BigInt::from(0),
+			BigInt::from(1),
+			BigInt::from(2),
+			BigInt::from(1) << 64,
+			BigInt::from(1) << 128,
+			(BigInt::from(1) << 128) - 1,
+			&prime / 2,
+			&prime - 2,
+			&prime - 1,
+		];
+		Self {
+			rng: StdRng::seed_from_u64(seed),
+			prime,
+			edge_cases,
+		}
+	}
+
+	/// Returns a felt: an edge case, a small value or a value uniformly drawn from the field
+	pub fn felt(&mut self) -> BigInt {
+		match self.rng.gen_range(0..10) {
+			0 => self.edge_cases[self.rng.gen_range(0..self.edge_cases.len())].clone(),
+			1 => BigInt::from(self.rng.gen_range(0..1000u64)),
+			_ => {
+				let bytes: [u8; 32] = self.rng.gen();
+				BigInt::from_bytes_be(Sign::Plus, &bytes) % &self.prime
+			},
+		}
+	}
+
+	pub fn felts(&mut self, count: usize) -> Vec<BigInt> {
+		(0..count).map(|_| self.felt()).collect()
+	}
+
+	/// Returns a random index below `count`
+	pub fn index(&mut self, count: usize) -> usize {
+		self.rng.gen_range(0..count)
+	}
+
+	/// Returns a felt between `low` and `high` included, favoring the bounds
+	pub fn felt_in_range(&mut self, low: &BigInt, high: &BigInt) -> BigInt {
+		let high = std::cmp::min(high.clone(), &self.prime - 1);
+		match self.rng.gen_range(0..10) {
+			0 => low.clone(),
+			1 => high,
+			_ => {
+				// More bytes than a felt, so that the modulo bias is negligible
+				let mut bytes = [0u8; 48];
+				self.rng.fill(&mut bytes[..]);
+				low + BigInt::from_bytes_be(Sign::Plus, &bytes) % (high - low + 1)
+			},
+		}
+	}
+
+	/// Returns an input for arguments bounded by the given ranges, if any
+	pub fn input(&mut self, ranges: &[Option<FuzzRange>]) -> Vec<BigInt> {
+		ranges
+			.iter()
+			.map(|range| match range {
+				Some((low, high)) => self.felt_in_range(low, high),
+				None => self.felt(),
+			})
+			.collect()
+	}
+}
+
+/// Shrink a failing input towards simpler felts: zero, small values and values right below
+/// the prime (small negative values).
+///
+/// `fails` tells whether the test still fails with a candidate input. Arguments are shrunk one
+/// at a time, keeping the simplest candidate the test still fails with, until no argument can
+/// be simplified anymore or `MAX_SHRINK_RUNS` candidates were tried.
+pub fn shrink(
+	input: &[BigInt],
+	prime: &BigInt,
+	mut fails: impl FnMut(&[BigInt]) -> bool,
+) -> Vec<BigInt> {
+	let mut input = input.to_vec();
+	let mut runs = 0;
+
+	'shrinking: loop {
+		for i in 0..input.len() {
+			for candidate in shrink_candidates(&input[i], prime) {
+				if runs == MAX_SHRINK_RUNS {
+					break 'shrinking
+				}
+				runs += 1;
+
+				let mut candidate_input = input.clone();
+				candidate_input[i] = candidate;
+				if fails(&candidate_input) {
+					input = candidate_input;
+					continue 'shrinking
+				}
+			}
+		}
+		break
+	}
+	input
+}
+
+/// Returns felts simpler than `value`, simplest first
+///
+/// Felts above half the prime are seen as negative values, a felt being simpler than another
+/// one when it is closer to zero. Besides zero and one, the candidates get closer and closer
+/// to `value`, so that the boundary of a failing range is found in logarithmic time.
+fn shrink_candidates(value: &BigInt, prime: &BigInt) -> Vec<BigInt> {
+	let signed = if value > &(prime / 2) {
+		value - prime
+	} else {
+		value.clone()
+	};
+	let magnitude = signed.abs();
+
+	let mut candidates = vec![BigInt::from(0), BigInt::from(1), BigInt::from(-1)];
+	let mut delta = &magnitude / 2;
+	while delta > BigInt::from(0) {
+		candidates.push(&signed - signed.signum() * &delta);
+		delta /= 2;
+	}
+
+	let mut simpler: Vec<BigInt> = Vec::new();
+	for candidate in candidates {
+		if candidate.abs() < magnitude && !simpler.contains(&candidate) {
+			simpler.push(candidate);
+		}
+	}
+	simpler
+		.into_iter()
+		.map(|candidate| {
+			if candidate.is_negative() {
+				candidate + prime
+			} else {
+				candidate
+			}
+		})
+		.collect()
+}
@@ -0,0 +1,28 @@
+%builtins range_check
+
+from starkware.cairo.common.math import split_felt
+
+struct Point {
+    x: felt,
+    y: felt,
+}
+
+func test_addition_commutes(a: felt, b: felt) {
+    assert a + b = b + a;
+    return ();
+}
+
+func test_split_felt{range_check_ptr}(value: felt) {
+    let (high, low) = split_felt(value);
+    assert value = high * 2 ** 128 + low;
+    return ();
+}
+
+func test_always_zero(value: felt) {
+    assert value = 0;
+    return ();
+}
+
+func test_struct_argument(point: Point) {
+    return ();
+}
@@ -0,0 +1,42 @@
+%lang starknet
+
+from starkware.cairo.common.math import assert_le
+
+@contract_interface
+namespace IVault {
+    func balance() -> (balance: felt) {
+    }
+
+    func deposits() -> (deposits: felt) {
+    }
+
+    func withdrawals() -> (withdrawals: felt) {
+    }
+}
+
+func invariant_balance_is_deposits_minus_withdrawals{syscall_ptr: felt*, range_check_ptr}(
+    vault: felt
+) {
+    %{ target_contract("src/fuzz/test_cairo_programs/vault.cairo") %}
+    let (balance) = IVault.balance(vault);
+    let (deposits) = IVault.deposits(vault);
+    let (withdrawals) = IVault.withdrawals(vault);
+    assert balance = deposits - withdrawals;
+    return ();
+}
+
+func invariant_balance_below_1000{syscall_ptr: felt*, range_check_ptr}(vault: felt) {
+    %{ target_contract("src/fuzz/test_cairo_programs/vault.cairo") %}
+    let (balance) = IVault.balance(vault);
+    assert_le(balance, 1000);
+    return ();
+}
+
+func invariant_without_target_contract() {
+    return ();
+}
+
+func invariant_with_looping_target() {
+    %{ target_contract("src/fuzz/test_cairo_programs/looping.cairo") %}
+    return ();
+}
@@ -0,0 +1,8 @@
+%lang starknet
+
+// Never returns, calls to it only stop once they run out of steps
+@external
+func spin(value: felt) {
+    spin(value);
+    return ();
+}
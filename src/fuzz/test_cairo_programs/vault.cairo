@@ -0,0 +1,60 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.cairo.common.math import assert_nn_le
+
+@storage_var
+func balance_() -> (balance: felt) {
+}
+
+@storage_var
+func deposits_() -> (deposits: felt) {
+}
+
+@storage_var
+func withdrawals_() -> (withdrawals: felt) {
+}
+
+@external
+func deposit{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(amount: felt) {
+    assert_nn_le(amount, 2 ** 64);
+    let (balance) = balance_.read();
+    balance_.write(balance + amount);
+    let (deposits) = deposits_.read();
+    deposits_.write(deposits + amount);
+    return ();
+}
+
+@external
+func withdraw{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(amount: felt) {
+    let (balance) = balance_.read();
+    assert_nn_le(amount, balance);
+    balance_.write(balance - amount);
+    let (withdrawals) = withdrawals_.read();
+    withdrawals_.write(withdrawals + amount);
+    return ();
+}
+
+@view
+func balance{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (
+    balance: felt
+) {
+    let (balance) = balance_.read();
+    return (balance=balance);
+}
+
+@view
+func deposits{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (
+    deposits: felt
+) {
+    let (deposits) = deposits_.read();
+    return (deposits=deposits);
+}
+
+@view
+func withdrawals{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (
+    withdrawals: felt
+) {
+    let (withdrawals) = withdrawals_.read();
+    return (withdrawals=withdrawals);
+}
@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+
+use num_bigint::BigInt;
+use num_traits::Num;
+use rstest::rstest;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{shrink, shrink_sequence, target_functions, Call, Corpus, FeltGenerator, FuzzConfig};
+use crate::cli::commands::test::{
+	tests::{run_single_test, run_single_test_with_options, test_options},
+	TestCommandError, TestOptions, TestStatus,
+};
+
+fn prime() -> BigInt {
+	BigInt::from_str_radix(
+		"800000000000011000000000000000000000000000000000000000000000001",
+		16,
+	)
+	.unwrap()
+}
+
+#[test]
+fn felts_are_reproducible_from_the_seed() {
+	let felts = FeltGenerator::new(42, prime()).felts(50);
+
+	assert_eq!(felts, FeltGenerator::new(42, prime()).felts(50));
+	assert_ne!(felts, FeltGenerator::new(43, prime()).felts(50));
+}
+
+#[test]
+fn felts_are_in_the_field() {
+	let mut generator = FeltGenerator::new(0, prime());
+
+	for felt in generator.felts(1000) {
+		assert!(felt >= BigInt::from(0) && felt < prime(), "{felt}");
+	}
+}
+
+#[test]
+fn edge_cases_are_generated() {
+	let felts = FeltGenerator::new(0, prime()).felts(1000);
+
+	assert!(felts.contains(&BigInt::from(0)));
+	assert!(felts.contains(&(prime() - 1)));
+}
+
+#[test]
+fn felts_in_range_are_bounded() {
+	let mut generator = FeltGenerator::new(0, prime());
+	let (low, high) = (BigInt::from(10), BigInt::from(20));
+
+	let felts: Vec<_> = (0..1000).map(|_| generator.felt_in_range(&low, &high)).collect();
+	assert!(felts.iter().all(|felt| &low <= felt && felt <= &high));
+	assert!(felts.contains(&low) && felts.contains(&high));
+}
+
+#[rstest]
+#[case("test_addition_commutes", TestStatus::SUCCESS)]
+#[case("test_split_felt", TestStatus::SUCCESS)]
+#[case("test_always_zero", TestStatus::FAILURE)]
+#[case("test_struct_argument", TestStatus::FAILURE)]
+fn fuzz(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/fuzz.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn falsifying_input_is_reported() {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/fuzz.cairo");
+	let result = run_single_test("test_always_zero", &path, 1000000).unwrap();
+
+	// The first random input fails and is shrunk
+	assert!(
+		result.output.contains("Falsifying input (run 1/100, seed 0): value=1\n"),
+		"{}",
+		result.output
+	);
+}
+
+#[test]
+fn inputs_are_shrunk_to_the_simplest_failing_felts() {
+	// Fails for any non zero value
+	assert_eq!(
+		vec![BigInt::from(1)],
+		shrink(&[prime() / 3], &prime(), |input| input[0]
+			!= BigInt::from(0))
+	);
+	// Fails for positive values above a threshold
+	assert_eq!(
+		vec![BigInt::from(1000), BigInt::from(0)],
+		shrink(&[BigInt::from(123456), prime() - 42], &prime(), |input| {
+			input[0] >= BigInt::from(1000) && input[0] < prime() / 2
+		})
+	);
+	// Fails for negative values
+	assert_eq!(
+		vec![prime() - 1],
+		shrink(&[prime() - 123456], &prime(), |input| input[0]
+			> prime() / 2)
+	);
+	// Already minimal
+	assert_eq!(
+		vec![BigInt::from(0)],
+		shrink(&[BigInt::from(0)], &prime(), |_| true)
+	);
+}
+
+#[test]
+fn corpus_is_persisted() {
+	let path = std::env::temp_dir().join(format!("cairo_foundry_corpus_{}.json", Uuid::new_v4()));
+
+	let mut corpus = Corpus::open_file(path.clone());
+	assert!(corpus.inputs().is_empty());
+	corpus.add(vec![BigInt::from(1), prime() - 1]).unwrap();
+	corpus.add(vec![BigInt::from(1), prime() - 1]).unwrap();
+	corpus.add(vec![BigInt::from(0), BigInt::from(2)]).unwrap();
+
+	assert_eq!(
+		vec![
+			vec![BigInt::from(1), prime() - 1],
+			vec![BigInt::from(0), BigInt::from(2)]
+		],
+		Corpus::open_file(path).inputs()
+	);
+}
+
+#[test]
+fn target_functions_are_external_functions_taking_felts() {
+	let abi = json!([
+		{"type": "function", "name": "deposit", "inputs": [{"name": "amount", "type": "felt"}]},
+		{"type": "function", "name": "balance", "inputs": [], "stateMutability": "view"},
+		{"type": "function", "name": "transfer", "inputs": [{"name": "amount", "type": "Uint256"}]},
+		{"type": "constructor", "name": "constructor", "inputs": []},
+	]);
+
+	let functions = target_functions(&abi);
+	assert_eq!(1, functions.len());
+	assert_eq!("deposit", functions[0].name);
+	assert_eq!(vec![String::from("amount")], functions[0].inputs);
+}
+
+#[test]
+fn call_sequences_are_shrunk() {
+	let call = |function, amount: u64| Call {
+		function,
+		calldata: vec![BigInt::from(amount)],
+	};
+	// Fails when the deposits (function 0) add up to more than 1000
+	let fails = |sequence: &[Call]| {
+		let deposits: BigInt = sequence
+			.iter()
+			.filter(|call| call.function == 0)
+			.map(|call| call.calldata[0].clone())
+			.sum();
+		deposits > BigInt::from(1000) && deposits < prime() / 2
+	};
+
+	assert_eq!(
+		vec![call(0, 1001)],
+		shrink_sequence(
+			&[call(0, 5), call(1, 3), call(0, 123456), call(0, 7)],
+			&prime(),
+			fails
+		)
+	);
+}
+
+#[rstest]
+#[case("invariant_balance_is_deposits_minus_withdrawals", TestStatus::SUCCESS)]
+#[case("invariant_balance_below_1000", TestStatus::FAILURE)]
+#[case("invariant_without_target_contract", TestStatus::FAILURE)]
+fn invariants(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/invariants.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn calls_to_a_looping_target_run_out_of_steps() -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/invariants.cairo");
+	let result = run_single_test_with_options(
+		"invariant_with_looping_target",
+		&path,
+		&TestOptions {
+			fuzz: FuzzConfig {
+				runs: 2,
+				seed: 0,
+				depth: 2,
+			},
+			..test_options(10000)
+		},
+	)?;
+
+	// Every call fails and is reverted
+	assert_eq!(TestStatus::SUCCESS, result.success, "{}", result.output);
+	assert!(result.output.contains("calls: 0"), "{}", result.output);
+	Ok(())
+}
+
+#[test]
+fn failing_call_sequence_is_reported() {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/invariants.cairo");
+	let result = run_single_test("invariant_balance_below_1000", &path, 1000000).unwrap();
+
+	assert!(
+		result.output.contains("Failing call sequence (run "),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("  deposit(amount="),
+		"{}",
+		result.output
+	);
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_integer_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::{ApTracking, HintParams},
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use num_traits::{Num, Signed};
+use serde_json::Value;
+
+use super::hint_processor::function_like_hint_processor::split_args;
+
+lazy_static! {
+	/// Prime of the field Starknet felts live in: 2^251 + 17 * 2^192 + 1
+	pub(crate) static ref FIELD_PRIME: BigInt =
+		(BigInt::from(1) << 251) + (BigInt::from(17) << 192) + BigInt::from(1);
+}
+
+/// Returns the value of a function-like hint argument.
+///
+/// An argument is either a literal (`42`, `-1`, `0x2a`, `2**128`) or a cairo variable
+/// (`ids.amount`).
+/// Keyword arguments (`target=0x2a`) are evaluated as their value.
+pub(crate) fn felt_arg(
+	arg: &str,
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<BigInt, VirtualMachineError> {
+	let value = arg.split_once('=').map_or(arg, |(_, value)| value).trim();
+
+	if let Some(name) = value.strip_prefix("ids.") {
+		return Ok((*get_integer_from_var_name(name, vm, ids_data, ap_tracking)?).clone())
+	}
+
+	felt_literal(value)
+		.ok_or_else(|| VirtualMachineError::CustomHint(format!("invalid hint argument '{arg}'")))
+}
+
+/// Returns the value of a felt literal: `42`, `-1`, `0x2a` or a power such as `2**128`
+///
+/// The value is reduced into `[0, P)`, so that `-1` is read as `P - 1`.
+pub(crate) fn felt_literal(value: &str) -> Option<BigInt> {
+	integer_literal(value).map(|value| reduce_felt(&value))
+}
+
+fn integer_literal(value: &str) -> Option<BigInt> {
+	// `-2**128` is `-(2**128)`
+	if let Some(value) = value.strip_prefix('-') {
+		return integer_literal(value.trim()).map(|value| -value)
+	}
+	// Powers are computed modulo the prime, so that large exponents stay cheap
+	if let Some((base, exponent)) = value.split_once("**") {
+		let base = integer_literal(base.trim())?;
+		let exponent = integer_literal(exponent.trim())?;
+		if exponent.is_negative() {
+			return None
+		}
+		return Some(base.modpow(&exponent, &FIELD_PRIME))
+	}
+
+	match value.strip_prefix("0x") {
+		Some(hex) => BigInt::from_str_radix(hex, 16).ok(),
+		None => BigInt::from_str_radix(value, 10).ok(),
+	}
+}
+
+/// Reduce an integer modulo the field prime, into `[0, P)`
+pub(crate) fn reduce_felt(value: &BigInt) -> BigInt {
+	((value % &*FIELD_PRIME) + &*FIELD_PRIME) % &*FIELD_PRIME
+}
+
+/// Returns the values of a list argument (`[1, ids.amount]`)
+pub(crate) fn felt_list_arg(
+	arg: &str,
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let value = arg.split_once('=').map_or(arg, |(_, value)| value).trim();
+	let items = value
+		.strip_prefix('[')
+		.and_then(|value| value.strip_suffix(']'))
+		.ok_or_else(|| VirtualMachineError::CustomHint(format!("expected a list, got '{arg}'")))?;
+
+	split_args(items)
+		.iter()
+		.filter(|item| !item.is_empty())
+		.map(|item| felt_arg(item, vm, ids_data, ap_tracking))
+		.collect()
+}
+
+/// Returns the values of a list of felt literals (`[1, 2**128]`)
+pub(crate) fn literal_list(arg: &str) -> Option<Vec<BigInt>> {
+	let items = arg.trim().strip_prefix('[')?.strip_suffix(']')?;
+	split_args(items)
+		.iter()
+		.filter(|item| !item.is_empty())
+		.map(|item| felt_literal(item))
+		.collect()
+}
+
+/// Returns the arguments of the function-like hints with the given name of a function
+///
+/// Used to read hints configuring how a test is run before running it, so their arguments
+/// can only be evaluated if they are literals.
+pub(crate) fn function_hint_args<'a>(
+	hints: &'a HashMap<usize, Vec<HintParams>>,
+	function: &'a str,
+	hint_name: &'a str,
+) -> impl Iterator<Item = Vec<String>> + 'a {
+	hints
+		.values()
+		.flatten()
+		.filter(move |hint| hint.accessible_scopes.last().map(String::as_str) == Some(function))
+		.filter_map(move |hint| {
+			let args =
+				hint.code.trim().strip_prefix(hint_name)?.strip_prefix('(')?.strip_suffix(')')?;
+			Some(split_args(args))
+		})
+}
+
+/// Returns the name of the cairo variable a hint should write its result to (`ids.result`)
+pub(crate) fn output_var(arg: &str) -> Result<&str, VirtualMachineError> {
+	arg.trim().strip_prefix("ids.").ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("expected a cairo variable, got '{arg}'"))
+	})
+}
+
+/// Returns the content of a quoted function-like hint argument (`"balance"` or `'balance'`)
+pub(crate) fn string_arg(arg: &str) -> Result<&str, VirtualMachineError> {
+	let value = arg.split_once('=').map_or(arg, |(_, value)| value).trim();
+	value
+		.strip_prefix('"')
+		.and_then(|value| value.strip_suffix('"'))
+		.or_else(|| value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')))
+		.ok_or_else(|| VirtualMachineError::CustomHint(format!("expected a string, got '{arg}'")))
+}
+
+/// Read a felt given either as a JSON number or as a decimal or hexadecimal string
+///
+/// Like literals, the value is reduced into `[0, P)`.
+pub(crate) fn felt_from_json(value: &Value) -> Option<BigInt> {
+	let value = match value {
+		Value::Number(number) =>
+			number.as_u64().map(BigInt::from).or_else(|| number.as_i64().map(BigInt::from)),
+		Value::String(string) => match string.strip_prefix("0x") {
+			Some(hex) => BigInt::from_str_radix(hex, 16).ok(),
+			None => BigInt::from_str_radix(string, 10).ok(),
+		},
+		_ => None,
+	};
+	value.map(|value| reduce_felt(&value))
+}
+
+/// Returns the non empty arguments of a function-like hint
+///
+/// `hint()` is parsed as a single empty argument by the hint processor.
+pub(crate) fn non_empty_args(args: &[String]) -> Vec<&str> {
+	args.iter().map(|arg| arg.as_str()).filter(|arg| !arg.is_empty()).collect()
+}
+
+/// Error returned when a hint is called with unexpected arguments
+pub(crate) fn wrong_arguments(expected_signature: &str) -> VirtualMachineError {
+	VirtualMachineError::CustomHint(format!("wrong arguments, expected {expected_signature}"))
+}
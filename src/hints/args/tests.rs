@@ -0,0 +1,97 @@
+use assert_matches::assert_matches;
+use cairo_rs::vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine};
+use num_bigint::BigInt;
+use serde_json::json;
+
+use super::{
+	felt_arg, felt_from_json, felt_list_arg, felt_literal, literal_list, non_empty_args,
+	output_var, string_arg, FIELD_PRIME,
+};
+
+#[test]
+fn felt_arg_literals() {
+	let vm = VirtualMachine::new(Default::default(), false, vec![]);
+	let parse = |arg: &str| felt_arg(arg, &vm, &Default::default(), &Default::default());
+
+	assert_eq!(BigInt::from(42), parse("42").unwrap());
+	assert_eq!(&*FIELD_PRIME - 1, parse("-1").unwrap());
+	assert_eq!(BigInt::from(42), parse("0x2a").unwrap());
+	assert_eq!(BigInt::from(42), parse("target=0x2a").unwrap());
+	assert_eq!(BigInt::from(1) << 128, parse("2**128").unwrap());
+	assert_eq!(BigInt::from(1000), parse("10 ** 3").unwrap());
+	assert_eq!(BigInt::from(0), parse(&FIELD_PRIME.to_string()).unwrap());
+	assert_eq!(
+		&*FIELD_PRIME - (BigInt::from(1) << 128),
+		parse("-2**128").unwrap()
+	);
+	assert_matches!(parse("forty-two"), Err(VirtualMachineError::CustomHint(_)));
+}
+
+#[test]
+fn powers_are_computed_modulo_the_prime() {
+	assert_eq!(
+		Some((BigInt::from(1) << 256) % &*FIELD_PRIME),
+		felt_literal("2**256")
+	);
+	assert!(felt_literal("2**4000000000").is_some());
+	assert_eq!(None, felt_literal("2**-1"));
+}
+
+#[test]
+fn felts_from_json_are_reduced() {
+	assert_eq!(Some(BigInt::from(42)), felt_from_json(&json!("0x2a")));
+	assert_eq!(Some(&*FIELD_PRIME - 1), felt_from_json(&json!(-1)));
+	assert_eq!(Some(&*FIELD_PRIME - 1), felt_from_json(&json!("-1")));
+	assert_eq!(None, felt_from_json(&json!([])));
+}
+
+#[test]
+fn felt_list_arg_literals() {
+	let vm = VirtualMachine::new(Default::default(), false, vec![]);
+	let parse = |arg| felt_list_arg(arg, &vm, &Default::default(), &Default::default());
+
+	assert_eq!(
+		vec![BigInt::from(1), BigInt::from(42)],
+		parse("[1, 0x2a]").unwrap()
+	);
+	assert!(parse("[]").unwrap().is_empty());
+	assert_matches!(parse("1, 2"), Err(VirtualMachineError::CustomHint(_)));
+}
+
+#[test]
+fn literal_lists() {
+	assert_eq!(
+		Some(vec![BigInt::from(1), BigInt::from(1) << 128]),
+		literal_list("[1, 2**128]")
+	);
+	assert_eq!(Some(vec![]), literal_list("[]"));
+	assert_eq!(None, literal_list("[1, ids.amount]"));
+}
+
+#[test]
+fn output_var_needs_ids_prefix() {
+	assert_eq!("result", output_var("ids.result").unwrap());
+	assert_matches!(
+		output_var("result"),
+		Err(VirtualMachineError::CustomHint(_))
+	);
+}
+
+#[test]
+fn string_arg_quotes() {
+	assert_eq!("balance", string_arg("\"balance\"").unwrap());
+	assert_eq!("balance", string_arg("'balance'").unwrap());
+	assert_matches!(
+		string_arg("balance"),
+		Err(VirtualMachineError::CustomHint(_))
+	);
+}
+
+#[test]
+fn empty_args_are_ignored() {
+	assert!(non_empty_args(&[String::new()]).is_empty());
+	assert_eq!(
+		vec!["ids.a", "2"],
+		non_empty_args(&[String::from("ids.a"), String::from("2")])
+	);
+}
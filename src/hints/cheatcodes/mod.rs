@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::{
+	args::{felt_arg, non_empty_args, wrong_arguments},
+	syscalls::get_starknet_state,
+};
+
+/// Change the caller address returned by the `get_caller_address` syscall
+///
+/// Without a target, the prank applies to every contract. With a target, it only applies
+/// to the contract deployed at the target address and takes precedence over the former.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_only_owner{syscall_ptr: felt*}() {
+///     %{ start_prank(ids.owner, target=ids.contract_address) %}
+///     ...
+///     %{ stop_prank(target=ids.contract_address) %}
+/// }
+/// ```
+pub fn start_prank(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (caller_address, target) = match non_empty_args(args)[..] {
+		[caller_address] => (felt_arg(caller_address, vm, ids_data, ap_tracking)?, None),
+		[caller_address, target] => (
+			felt_arg(caller_address, vm, ids_data, ap_tracking)?,
+			Some(felt_arg(target, vm, ids_data, ap_tracking)?),
+		),
+		_ => return Err(wrong_arguments("start_prank(caller_address, target?)")),
+	};
+
+	let state = get_starknet_state(exec_scopes)?;
+	match target {
+		Some(target) => {
+			state.targeted_pranks.insert(target, caller_address);
+		},
+		None => state.prank = Some(caller_address),
+	}
+	Ok(())
+}
+
+/// Cancel a prank started with `start_prank`, with or without a target
+pub fn stop_prank(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let target = match non_empty_args(args)[..] {
+		[] => None,
+		[target] => Some(felt_arg(target, vm, ids_data, ap_tracking)?),
+		_ => return Err(wrong_arguments("stop_prank(target?)")),
+	};
+
+	let state = get_starknet_state(exec_scopes)?;
+	match target {
+		Some(target) => {
+			state.targeted_pranks.remove(&target);
+		},
+		None => state.prank = None,
+	}
+	Ok(())
+}
+
+/// Set the block timestamp returned by the `get_block_timestamp` syscall
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_time_lock{syscall_ptr: felt*}() {
+///     %{ warp(1672531200) %}
+///     ...
+/// }
+/// ```
+pub fn warp(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let block_timestamp = match non_empty_args(args)[..] {
+		[block_timestamp] => felt_arg(block_timestamp, vm, ids_data, ap_tracking)?,
+		_ => return Err(wrong_arguments("warp(block_timestamp)")),
+	};
+
+	get_starknet_state(exec_scopes)?.block_timestamp = block_timestamp;
+	Ok(())
+}
+
+/// Set the block number returned by the `get_block_number` syscall
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_after_some_blocks{syscall_ptr: felt*}() {
+///     %{ roll(42) %}
+///     ...
+/// }
+/// ```
+pub fn roll(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let block_number = match non_empty_args(args)[..] {
+		[block_number] => felt_arg(block_number, vm, ids_data, ap_tracking)?,
+		_ => return Err(wrong_arguments("roll(block_number)")),
+	};
+
+	get_starknet_state(exec_scopes)?.block_number = block_number;
+	Ok(())
+}
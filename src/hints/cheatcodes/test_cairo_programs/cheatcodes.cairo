@@ -0,0 +1,46 @@
+%lang starknet
+
+from starkware.starknet.common.syscalls import (
+    get_block_number,
+    get_block_timestamp,
+    get_caller_address,
+)
+
+func test_prank{syscall_ptr: felt*}() {
+    %{ start_prank(0x123) %}
+    let (pranked_caller) = get_caller_address();
+    assert 0x123 = pranked_caller;
+
+    %{ stop_prank() %}
+    let (caller) = get_caller_address();
+    assert 0 = caller;
+    return ();
+}
+
+func test_targeted_prank{syscall_ptr: felt*}() {
+    tempvar owner = 456;
+    %{ start_prank(ids.owner, target=1) %}
+    %{ start_prank(789, target=2) %}
+    let (pranked_caller) = get_caller_address();
+    assert 456 = pranked_caller;
+
+    %{ stop_prank(target=1) %}
+    let (caller) = get_caller_address();
+    assert 0 = caller;
+    return ();
+}
+
+func test_warp_and_roll{syscall_ptr: felt*}() {
+    %{ warp(1672531200) %}
+    %{ roll(42) %}
+    let (block_timestamp) = get_block_timestamp();
+    assert 1672531200 = block_timestamp;
+    let (block_number) = get_block_number();
+    assert 42 = block_number;
+    return ();
+}
+
+func test_prank_wrong_arguments{syscall_ptr: felt*}() {
+    %{ start_prank() %}
+    return ();
+}
@@ -0,0 +1,19 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_prank", TestStatus::SUCCESS)]
+#[case("test_targeted_prank", TestStatus::SUCCESS)]
+#[case("test_warp_and_roll", TestStatus::SUCCESS)]
+#[case("test_prank_wrong_arguments", TestStatus::FAILURE)]
+fn cheatcodes(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
@@ -0,0 +1,608 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::{
+		exec_scope::ExecutionScopes,
+		program::Program,
+		relocatable::{MaybeRelocatable, Relocatable},
+	},
+	vm::{
+		errors::vm_errors::VirtualMachineError, hook::Hooks, runners::cairo_runner::CairoRunner,
+		vm_core::VirtualMachine,
+	},
+};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde_json::Value;
+
+use super::{
+	args::{
+		felt_arg, felt_from_json, felt_list_arg, non_empty_args, output_var, string_arg,
+		wrong_arguments,
+	},
+	output_buffer::EXECUTION_UUID_VAR_NAME,
+	processor::setup_hint_processor,
+	sn_keccak,
+	syscalls::{get_starknet_state, StarknetState, STARKNET_STATE_VAR_NAME},
+	MOCK_CALL_KEY,
+};
+use crate::{
+	cairo_run::{
+		function_implicit_args, initialize_entrypoint, EntrypointArgs, COMPILE_OPTIONS_VAR_NAME,
+		DEFAULT_LAYOUT, LAYOUT_VAR_NAME,
+	},
+	compile::{compile_contract, CompileOptions, CompiledContract},
+	hooks::{CallTracer, CALL_TRACER_VAR_NAME, HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+	io::compiled_programs::{list_functions, FunctionSignature},
+};
+
+/// Type of a contract entry point, as listed in `entry_points_by_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryPointType {
+	External,
+	L1Handler,
+	Constructor,
+}
+
+impl EntryPointType {
+	fn key(&self) -> &'static str {
+		match self {
+			Self::External => "EXTERNAL",
+			Self::L1Handler => "L1_HANDLER",
+			Self::Constructor => "CONSTRUCTOR",
+		}
+	}
+}
+
+/// A declared contract class: its program, its ABI and the offsets of its entry points
+#[derive(Debug, Clone)]
+pub struct ContractClass {
+	pub program: Program,
+	pub abi: Value,
+	entry_points: HashMap<(EntryPointType, BigInt), usize>,
+	/// Functions of the program by pc, for the calls made by the class to be traced
+	functions: HashMap<usize, FunctionSignature>,
+}
+
+impl ContractClass {
+	pub fn new(contract: CompiledContract) -> Result<Self, VirtualMachineError> {
+		let functions = list_functions(&contract.program_json);
+		let program = Program::from_json(contract.program_json, None).map_err(|e| {
+			VirtualMachineError::CustomHint(format!("invalid contract program: {e}"))
+		})?;
+
+		let mut entry_points = HashMap::new();
+		for entry_point_type in [
+			EntryPointType::External,
+			EntryPointType::L1Handler,
+			EntryPointType::Constructor,
+		] {
+			let list = contract.entry_points_by_type[entry_point_type.key()].as_array();
+			for entry_point in list.into_iter().flatten() {
+				let selector = felt_from_json(&entry_point["selector"]);
+				let offset = felt_from_json(&entry_point["offset"]).and_then(|o| o.to_usize());
+				match (selector, offset) {
+					(Some(selector), Some(offset)) => {
+						entry_points.insert((entry_point_type, selector), offset);
+					},
+					_ =>
+						return Err(VirtualMachineError::CustomHint(format!(
+							"invalid entry point {entry_point}"
+						))),
+				}
+			}
+		}
+
+		Ok(Self {
+			program,
+			abi: contract.abi,
+			entry_points,
+			functions,
+		})
+	}
+
+	/// Returns the offset of the entry point of the given type and selector
+	pub fn entry_point(
+		&self,
+		entry_point_type: EntryPointType,
+		selector: &BigInt,
+	) -> Option<usize> {
+		self.entry_points.get(&(entry_point_type, selector.clone())).copied()
+	}
+
+	/// Returns the offset of the constructor, if the class has one
+	pub fn constructor(&self) -> Option<usize> {
+		self.entry_points
+			.iter()
+			.find(|((entry_point_type, _), _)| *entry_point_type == EntryPointType::Constructor)
+			.map(|(_, offset)| *offset)
+	}
+}
+
+/// Compile a contract and declare its class in the state
+///
+/// The class hash is written to the cairo variable given as last argument.
+/// It is derived from the source of the contract and is not the class hash Starknet would
+/// compute, so it should not be hardcoded in tests.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_vault{syscall_ptr: felt*}() {
+///     tempvar class_hash;
+///     %{ declare("contracts/token.cairo", ids.class_hash) %}
+///     ...
+/// }
+/// ```
+pub fn declare(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (path, output) = match non_empty_args(args)[..] {
+		[path, output] => (PathBuf::from(string_arg(path)?), output_var(output)?),
+		_ => return Err(wrong_arguments("declare(path, ids.class_hash)")),
+	};
+
+	let compile_options =
+		exec_scopes.get::<CompileOptions>(COMPILE_OPTIONS_VAR_NAME).unwrap_or_default();
+	let class_hash = declare_contract(exec_scopes, &path, &compile_options)?;
+	insert_value_from_var_name(output, class_hash, vm, ids_data, ap_tracking)
+}
+
+/// Compile a contract and declare its class in the state
+///
+/// Returns the class hash, derived from the source of the contract.
+pub fn declare_contract(
+	exec_scopes: &mut ExecutionScopes,
+	path: &Path,
+	compile_options: &CompileOptions,
+) -> Result<BigInt, VirtualMachineError> {
+	let declare_error = |reason: String| {
+		VirtualMachineError::CustomHint(format!("failed to declare {}: {reason}", path.display()))
+	};
+
+	let source = fs::read(path).map_err(|e| declare_error(e.to_string()))?;
+	let contract = compile_contract(&path.to_path_buf(), compile_options)
+		.map_err(|e| declare_error(e.to_string()))?;
+	let class_hash = sn_keccak(&source);
+
+	get_starknet_state(exec_scopes)?
+		.classes
+		.insert(class_hash.clone(), ContractClass::new(contract)?);
+	Ok(class_hash)
+}
+
+/// Deploy a declared class, calling its constructor with the given calldata
+///
+/// The address of the new contract is written to the cairo variable given as last argument.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_vault{syscall_ptr: felt*}() {
+///     ...
+///     tempvar token_address;
+///     %{ deploy(ids.class_hash, [ids.owner, 1000], ids.token_address) %}
+///     ...
+/// }
+/// ```
+pub fn deploy(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (class_hash, calldata, output) = match non_empty_args(args)[..] {
+		[class_hash, calldata, output] => (
+			felt_arg(class_hash, vm, ids_data, ap_tracking)?,
+			felt_list_arg(calldata, vm, ids_data, ap_tracking)?,
+			output_var(output)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"deploy(class_hash, [calldata...], ids.contract_address)",
+			)),
+	};
+
+	let contract_address =
+		deploy_contract(exec_scopes, *vm.get_current_step(), &class_hash, &calldata)?;
+	insert_value_from_var_name(output, contract_address, vm, ids_data, ap_tracking)
+}
+
+/// Deploy a declared class as the current contract, calling its constructor with the given
+/// calldata
+///
+/// Returns the address of the new contract.
+pub fn deploy_contract(
+	exec_scopes: &mut ExecutionScopes,
+	current_step: usize,
+	class_hash: &BigInt,
+	calldata: &[BigInt],
+) -> Result<BigInt, VirtualMachineError> {
+	let state = get_starknet_state(exec_scopes)?;
+	let constructor = state
+		.classes
+		.get(class_hash)
+		.ok_or_else(|| {
+			VirtualMachineError::CustomHint(format!("class {class_hash:#x} is not declared"))
+		})?
+		.constructor();
+	let contract_address = state.next_contract_address();
+	let deployer_address = state.contract_address.clone();
+	state.contracts.insert(contract_address.clone(), class_hash.clone());
+
+	match constructor {
+		Some(offset) => {
+			execute_entry_point(
+				exec_scopes,
+				current_step,
+				class_hash,
+				offset,
+				&contract_address,
+				&deployer_address,
+				calldata,
+			)?;
+		},
+		None if !calldata.is_empty() =>
+			return Err(VirtualMachineError::CustomHint(format!(
+				"class {class_hash:#x} has no constructor, calldata should be empty"
+			))),
+		None => {},
+	}
+
+	Ok(contract_address)
+}
+
+/// Call an external function of a deployed contract, as the current contract
+///
+/// Returns the data returned by the function.
+pub fn call_contract(
+	exec_scopes: &mut ExecutionScopes,
+	current_step: usize,
+	contract_address: &BigInt,
+	selector: &BigInt,
+	calldata: &[BigInt],
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let state = get_starknet_state(exec_scopes)?;
+	let class_hash = deployed_class_hash(state, contract_address)?;
+	let offset = entry_point_offset(
+		state,
+		contract_address,
+		&class_hash,
+		EntryPointType::External,
+		selector,
+	)?;
+	let caller_address = state.contract_address.clone();
+
+	execute_entry_point(
+		exec_scopes,
+		current_step,
+		&class_hash,
+		offset,
+		contract_address,
+		&caller_address,
+		calldata,
+	)
+}
+
+/// Execute an external function of a declared class in the context of the current contract
+///
+/// Returns the data returned by the function.
+pub fn library_call(
+	exec_scopes: &mut ExecutionScopes,
+	current_step: usize,
+	class_hash: &BigInt,
+	selector: &BigInt,
+	calldata: &[BigInt],
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let state = get_starknet_state(exec_scopes)?;
+	let contract_address = state.contract_address.clone();
+	let caller_address = state.caller_address.clone();
+	let offset = entry_point_offset(
+		state,
+		&contract_address,
+		class_hash,
+		EntryPointType::External,
+		selector,
+	)?;
+
+	execute_entry_point(
+		exec_scopes,
+		current_step,
+		class_hash,
+		offset,
+		&contract_address,
+		&caller_address,
+		calldata,
+	)
+}
+
+/// Invoke an `@l1_handler` of a deployed contract, as if a message was sent from L1
+///
+/// The handler gets the L1 sender as first argument, followed by the payload of the message.
+/// Returns the data returned by the handler.
+pub fn l1_handler(
+	exec_scopes: &mut ExecutionScopes,
+	current_step: usize,
+	contract_address: &BigInt,
+	selector: &BigInt,
+	from_address: &BigInt,
+	payload: &[BigInt],
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let state = get_starknet_state(exec_scopes)?;
+	let class_hash = deployed_class_hash(state, contract_address)?;
+	let offset = entry_point_offset(
+		state,
+		contract_address,
+		&class_hash,
+		EntryPointType::L1Handler,
+		selector,
+	)?;
+	let calldata: Vec<_> = std::iter::once(from_address.clone()).chain(payload.to_vec()).collect();
+
+	// L1 handlers are not called by an L2 contract
+	execute_entry_point(
+		exec_scopes,
+		current_step,
+		&class_hash,
+		offset,
+		contract_address,
+		&BigInt::from(0),
+		&calldata,
+	)
+}
+
+fn deployed_class_hash(
+	state: &StarknetState,
+	contract_address: &BigInt,
+) -> Result<BigInt, VirtualMachineError> {
+	state.contracts.get(contract_address).cloned().ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!(
+			"no contract is deployed at address {contract_address:#x}"
+		))
+	})
+}
+
+/// Returns the offset of an entry point of the class called at `contract_address`
+fn entry_point_offset(
+	state: &StarknetState,
+	contract_address: &BigInt,
+	class_hash: &BigInt,
+	entry_point_type: EntryPointType,
+	selector: &BigInt,
+) -> Result<usize, VirtualMachineError> {
+	state
+		.classes
+		.get(class_hash)
+		.ok_or_else(|| {
+			call_error(
+				contract_address,
+				format!("class {class_hash:#x} is not declared"),
+			)
+		})?
+		.entry_point(entry_point_type, selector)
+		.ok_or_else(|| {
+			call_error(
+				contract_address,
+				format!(
+					"class {class_hash:#x} has no {} entry point with selector {selector:#x}",
+					entry_point_type.key()
+				),
+			)
+		})
+}
+
+/// Error of a call to the contract at `contract_address`
+fn call_error(contract_address: &BigInt, reason: String) -> VirtualMachineError {
+	VirtualMachineError::CustomHint(format!(
+		"call to contract {contract_address:#x} failed: {reason}"
+	))
+}
+
+/// Execute an entry point of a declared class with a fresh `VirtualMachine`
+///
+/// The `StarknetState` is moved to the new execution, with `contract_address` as the current
+/// contract and `caller_address` as its caller, and moved back once the execution is over.
+/// A call which fails is reverted: the state is left as it was before the call.
+/// The entry point is run in the context of the calling execution, see ``inherit_context``.
+/// `current_step` is the step the calling execution is at: the entry point may only take the
+/// steps left to its caller, and its steps are counted against the `max_steps` of the calling
+/// execution, which is lowered by as many steps.
+///
+/// Returns the data returned by the entry point.
+pub fn execute_entry_point(
+	exec_scopes: &mut ExecutionScopes,
+	current_step: usize,
+	class_hash: &BigInt,
+	offset: usize,
+	contract_address: &BigInt,
+	caller_address: &BigInt,
+	calldata: &[BigInt],
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let state = get_starknet_state(exec_scopes)?;
+	let class = match state.classes.get(class_hash) {
+		Some(class) => class.clone(),
+		None =>
+			return Err(call_error(
+				contract_address,
+				format!("class {class_hash:#x} is not declared"),
+			)),
+	};
+	let snapshot = state.clone();
+
+	let mut state = std::mem::take(state);
+	let previous_context = (
+		std::mem::replace(&mut state.contract_address, contract_address.clone()),
+		std::mem::replace(&mut state.caller_address, caller_address.clone()),
+	);
+	let (result, mut state, steps) =
+		run_entry_point(&class, offset, calldata, state, exec_scopes, current_step);
+	(state.contract_address, state.caller_address) = previous_context;
+	*get_starknet_state(exec_scopes)? = if result.is_ok() { state } else { snapshot };
+	if let Ok(max_steps) = exec_scopes.get::<u64>(MAX_STEPS_VAR_NAME) {
+		exec_scopes.insert_value(MAX_STEPS_VAR_NAME, max_steps.saturating_sub(steps as u64));
+	}
+
+	result.map_err(|reason| call_error(contract_address, reason))
+}
+
+/// Run a contract entry point with the given state, in the context of the calling execution
+///
+/// Returns the result of the execution along with the state and the number of steps taken,
+/// whether it succeeded or not.
+fn run_entry_point(
+	class: &ContractClass,
+	offset: usize,
+	calldata: &[BigInt],
+	state: StarknetState,
+	caller_scopes: &ExecutionScopes,
+	current_step: usize,
+) -> (Result<Vec<BigInt>, String>, StarknetState, usize) {
+	let program = &class.program;
+	let layout = caller_scopes
+		.get::<String>(LAYOUT_VAR_NAME)
+		.unwrap_or_else(|_| DEFAULT_LAYOUT.to_string());
+	let mut cairo_runner = match CairoRunner::new(program, &layout, false) {
+		Ok(cairo_runner) => cairo_runner,
+		Err(e) => return (Err(e.to_string()), state, 0),
+	};
+	let mut vm = VirtualMachine::new(
+		program.prime.clone(),
+		false,
+		program.error_message_attributes.clone(),
+	);
+	cairo_runner.exec_scopes.insert_value(STARKNET_STATE_VAR_NAME, state);
+	cairo_runner.exec_scopes.insert_value(LAYOUT_VAR_NAME, layout);
+	inherit_context(
+		&mut cairo_runner.exec_scopes,
+		caller_scopes,
+		current_step,
+		&class.functions,
+	);
+
+	let mut hint_processor = setup_hint_processor();
+	let implicit_args = function_implicit_args(program, offset).unwrap_or_default();
+	let result = initialize_entrypoint(
+		&mut cairo_runner,
+		&mut vm,
+		offset,
+		&implicit_args,
+		EntrypointArgs::Calldata(calldata),
+	)
+	.map_err(|e| e.to_string())
+	.and_then(|end| {
+		cairo_runner
+			.run_until_pc(end, &mut vm, &mut hint_processor)
+			.map_err(|e| e.to_string())
+	})
+	.and_then(|_| {
+		cairo_runner
+			.end_run(true, false, &mut vm, &mut hint_processor)
+			.map_err(|e| e.to_string())
+	})
+	.and_then(|_| vm.verify_auto_deductions().map_err(|e| e.to_string()))
+	.and_then(|_| read_retdata(&vm).map_err(|e| e.to_string()));
+
+	let state = get_starknet_state(&mut cairo_runner.exec_scopes)
+		.map(std::mem::take)
+		.unwrap_or_default();
+	(result, state, *vm.get_current_step())
+}
+
+/// Copy the context of the calling execution to the execution scopes of a contract entry point
+///
+/// The entry point gets the options contracts are compiled with, the output buffer, the `Hooks`
+/// and the call tracer of its caller, its calls being rendered below the current call of the
+/// caller. It may take as many steps as its caller has left, its `max_steps` being lowered by
+/// the `current_step` of the caller.
+/// Mocks are not carried over, as they are set on the pcs of the calling program: the entry
+/// point starts without any.
+fn inherit_context(
+	exec_scopes: &mut ExecutionScopes,
+	caller_scopes: &ExecutionScopes,
+	current_step: usize,
+	functions: &HashMap<usize, FunctionSignature>,
+) {
+	let compile_options = caller_scopes
+		.get::<CompileOptions>(COMPILE_OPTIONS_VAR_NAME)
+		.unwrap_or_default();
+	exec_scopes.insert_value(COMPILE_OPTIONS_VAR_NAME, compile_options);
+	if let Ok(execution_uuid) = caller_scopes.get::<BigInt>(EXECUTION_UUID_VAR_NAME) {
+		exec_scopes.insert_value(EXECUTION_UUID_VAR_NAME, execution_uuid);
+	}
+	if let (Ok(hooks), Ok(max_steps)) = (
+		caller_scopes.get::<Hooks>(HOOKS_VAR_NAME),
+		caller_scopes.get::<u64>(MAX_STEPS_VAR_NAME),
+	) {
+		exec_scopes.insert_value(HOOKS_VAR_NAME, hooks);
+		exec_scopes.insert_value(
+			MAX_STEPS_VAR_NAME,
+			max_steps.saturating_sub(current_step as u64),
+		);
+	}
+	let opt_call_tracer = caller_scopes
+		.get_any_boxed_ref(CALL_TRACER_VAR_NAME)
+		.ok()
+		.and_then(|call_tracer| call_tracer.downcast_ref::<CallTracer>());
+	if let Some(call_tracer) = opt_call_tracer {
+		exec_scopes.insert_value(CALL_TRACER_VAR_NAME, call_tracer.nested(functions.clone()));
+	}
+	exec_scopes.insert_value(MOCK_CALL_KEY, HashMap::<usize, BigInt>::new());
+}
+
+/// Read the `(retdata_size, retdata)` returned by an entry point wrapper
+fn read_retdata(vm: &VirtualMachine) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let ap = vm.get_ap();
+	let retdata_size = (*vm.get_integer(&Relocatable {
+		segment_index: ap.segment_index,
+		offset: ap.offset - 2,
+	})?)
+	.to_usize()
+	.ok_or(VirtualMachineError::BigintToUsizeFail)?;
+	let retdata = vm
+		.get_relocatable(&Relocatable {
+			segment_index: ap.segment_index,
+			offset: ap.offset - 1,
+		})?
+		.into_owned();
+
+	(0..retdata_size)
+		.map(|i| Ok((*vm.get_integer(&(retdata + i))?).clone()))
+		.collect()
+}
+
+/// Write data returned to a contract in a new segment
+///
+/// Returns the pointer to the data.
+pub fn write_retdata(
+	vm: &mut VirtualMachine,
+	retdata: &[BigInt],
+) -> Result<MaybeRelocatable, VirtualMachineError> {
+	let retdata_ptr = vm.add_memory_segment();
+	for (i, value) in retdata.iter().enumerate() {
+		vm.insert_value(&(retdata_ptr + i), value)?;
+	}
+	Ok(retdata_ptr.into())
+}
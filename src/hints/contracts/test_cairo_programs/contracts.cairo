@@ -0,0 +1,66 @@
+%lang starknet
+
+@contract_interface
+namespace ICounter {
+    func increase(amount: felt) {
+    }
+
+    func get() -> (value: felt) {
+    }
+
+    func owner() -> (owner: felt) {
+    }
+}
+
+@contract_interface
+namespace ILooping {
+    func spin(value: felt) {
+    }
+}
+
+func test_deploy_and_call{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    local class_hash;
+    local counter_address;
+    %{ declare("src/hints/contracts/test_cairo_programs/counter.cairo", ids.class_hash) %}
+    %{ deploy(ids.class_hash, [10], ids.counter_address) %}
+
+    ICounter.increase(counter_address, 5);
+    let (value) = ICounter.get(counter_address);
+    assert 15 = value;
+
+    // The test contract deployed the counter
+    let (owner) = ICounter.owner(counter_address);
+    assert 1 = owner;
+    return ();
+}
+
+func test_library_call{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    local class_hash;
+    %{ declare("src/hints/contracts/test_cairo_programs/counter.cairo", ids.class_hash) %}
+
+    ICounter.library_call_increase(class_hash, 5);
+
+    // The storage of the test contract was used
+    tempvar value;
+    %{ load(1, "counter", ids.value) %}
+    assert 5 = value;
+    return ();
+}
+
+func test_call_undeployed_contract{syscall_ptr: felt*, range_check_ptr}() {
+    ICounter.increase(0x42, 5);
+    return ();
+}
+
+func test_call_looping_contract{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    local class_hash;
+    local looping_address;
+    %{ declare("src/fuzz/test_cairo_programs/looping.cairo", ids.class_hash) %}
+    %{ deploy(ids.class_hash, [], ids.looping_address) %}
+
+    ILooping.spin(looping_address, 0);
+    return ();
+}
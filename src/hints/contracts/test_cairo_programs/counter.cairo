@@ -0,0 +1,50 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.starknet.common.syscalls import get_caller_address
+
+@storage_var
+func counter() -> (value: felt) {
+}
+
+@storage_var
+func owner_() -> (owner: felt) {
+}
+
+@constructor
+func constructor{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    initial_value: felt
+) {
+    counter.write(initial_value);
+    let (caller) = get_caller_address();
+    owner_.write(caller);
+    return ();
+}
+
+@external
+func increase{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(amount: felt) {
+    let (value) = counter.read();
+    counter.write(value + amount);
+    return ();
+}
+
+@external
+func increase_and_fail{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    amount: felt
+) {
+    increase(amount);
+    assert 0 = 1;
+    return ();
+}
+
+@view
+func get{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (value: felt) {
+    let (value) = counter.read();
+    return (value=value);
+}
+
+@view
+func owner{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (owner: felt) {
+    let (owner) = owner_.read();
+    return (owner=owner);
+}
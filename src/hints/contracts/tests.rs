@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use cairo_rs::types::exec_scope::ExecutionScopes;
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use super::{call_contract, declare_contract, deploy_contract};
+use crate::{
+	cli::commands::test::{
+		tests::{run_single_test, run_single_test_with_options, test_options},
+		TestCommandError, TestOptions, TestStatus,
+	},
+	compile::CompileOptions,
+	hints::{
+		sn_keccak, storage_address,
+		syscalls::{get_starknet_state, StarknetState, STARKNET_STATE_VAR_NAME},
+	},
+	hooks::{setup_hooks, HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+};
+
+#[rstest]
+#[case("test_deploy_and_call", TestStatus::SUCCESS)]
+#[case("test_library_call", TestStatus::SUCCESS)]
+#[case("test_call_undeployed_contract", TestStatus::FAILURE)]
+fn contracts(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/contracts/test_cairo_programs/contracts.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn calls_made_by_contracts_are_traced() -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/contracts/test_cairo_programs/contracts.cairo");
+	let result = run_single_test_with_options(
+		"test_deploy_and_call",
+		&path,
+		&TestOptions {
+			trace_calls: true,
+			..test_options(1000000)
+		},
+	)?;
+	assert_eq!(TestStatus::SUCCESS, result.success, "{}", result.output);
+	assert!(
+		result.output.contains("├─ increase(5)"),
+		"{}",
+		result.output
+	);
+	Ok(())
+}
+
+#[test]
+fn steps_of_called_contracts_count_against_max_steps() -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/contracts/test_cairo_programs/contracts.cairo");
+	let result = run_single_test("test_call_looping_contract", &path, 10000)?;
+	assert_eq!(TestStatus::FAILURE, result.success, "{}", result.output);
+	assert!(
+		result.output.contains("max_steps reached"),
+		"{}",
+		result.output
+	);
+	Ok(())
+}
+
+#[test]
+fn failing_calls_are_reverted() {
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(STARKNET_STATE_VAR_NAME, StarknetState::default());
+	let class_hash = declare_contract(
+		&mut exec_scopes,
+		Path::new("src/hints/contracts/test_cairo_programs/counter.cairo"),
+		&CompileOptions::default(),
+	)
+	.unwrap();
+	let address = deploy_contract(&mut exec_scopes, 0, &class_hash, &[BigInt::from(10)]).unwrap();
+
+	let result = call_contract(
+		&mut exec_scopes,
+		0,
+		&address,
+		&sn_keccak(b"increase_and_fail"),
+		&[BigInt::from(5)],
+	);
+
+	assert!(result.is_err());
+	let state = get_starknet_state(&mut exec_scopes).unwrap();
+	assert_eq!(
+		BigInt::from(10),
+		state.contract_storage_read(&address, &storage_address("counter", &[]))
+	);
+}
+
+#[test]
+fn called_contracts_only_take_the_steps_left_to_their_caller() {
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(STARKNET_STATE_VAR_NAME, StarknetState::default());
+	exec_scopes.insert_value(HOOKS_VAR_NAME, setup_hooks());
+	exec_scopes.insert_value(MAX_STEPS_VAR_NAME, 1000000_u64);
+	let class_hash = declare_contract(
+		&mut exec_scopes,
+		Path::new("src/hints/contracts/test_cairo_programs/counter.cairo"),
+		&CompileOptions::default(),
+	)
+	.unwrap();
+	let address = deploy_contract(&mut exec_scopes, 0, &class_hash, &[BigInt::from(10)]).unwrap();
+
+	// The caller has already used most of its budget
+	let error = call_contract(
+		&mut exec_scopes,
+		999990,
+		&address,
+		&sn_keccak(b"increase"),
+		&[BigInt::from(5)],
+	)
+	.unwrap_err()
+	.to_string();
+
+	assert!(error.contains("max_steps reached"), "{error}");
+	let state = get_starknet_state(&mut exec_scopes).unwrap();
+	assert_eq!(
+		BigInt::from(10),
+		state.contract_storage_read(&address, &storage_address("counter", &[]))
+	);
+}
+
+#[test]
+fn calls_to_undeclared_classes_report_the_contract() {
+	let mut state = StarknetState::default();
+	state.contracts.insert(BigInt::from(0x42), BigInt::from(0x1234));
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(STARKNET_STATE_VAR_NAME, state);
+
+	let error = call_contract(
+		&mut exec_scopes,
+		0,
+		&BigInt::from(0x42),
+		&sn_keccak(b"get"),
+		&[],
+	)
+	.unwrap_err()
+	.to_string();
+
+	assert!(
+		error.contains("call to contract 0x42 failed: class 0x1234 is not declared"),
+		"{error}"
+	);
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use starknet_crypto::{get_public_key, rfc6979_generate_k};
+
+use super::{
+	args::{felt_arg, non_empty_args, output_var, wrong_arguments},
+	sn_keccak,
+	storage::{from_field_element, to_field_element},
+};
+
+/// A STARK curve keypair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPair {
+	pub private_key: BigInt,
+	pub public_key: BigInt,
+}
+
+impl KeyPair {
+	pub fn new(private_key: BigInt) -> Self {
+		let public_key = from_field_element(&get_public_key(&to_field_element(&private_key)));
+		Self {
+			private_key,
+			public_key,
+		}
+	}
+
+	/// Returns the keypair of the test account of the given index
+	///
+	/// The private key is the `sn_keccak` of `"cairo-foundry test account <index>"`,
+	/// so that accounts are the same across runs and machines.
+	pub fn test_account(index: u64) -> Self {
+		Self::new(sn_keccak(
+			format!("cairo-foundry test account {index}").as_bytes(),
+		))
+	}
+}
+
+/// Sign a message hash with a private key, returning the `(r, s)` signature
+///
+/// The nonce is derived from the message hash and the private key as described in RFC 6979,
+/// so a message is always signed the same way.
+pub fn sign_message(
+	private_key: &BigInt,
+	message_hash: &BigInt,
+) -> Result<(BigInt, BigInt), VirtualMachineError> {
+	let private_key = to_field_element(private_key);
+	let message = to_field_element(message_hash);
+	let k = rfc6979_generate_k(&message, &private_key, None);
+
+	let signature = starknet_crypto::sign(&private_key, &message, &k).map_err(|e| {
+		VirtualMachineError::CustomHint(format!("failed to sign {message_hash:#x}: {e}"))
+	})?;
+	Ok((
+		from_field_element(&signature.r),
+		from_field_element(&signature.s),
+	))
+}
+
+/// Sign a message hash with a private key on the STARK curve
+///
+/// The `r` and `s` parts of the signature are written to the last two cairo variables.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_verify{ecdsa_ptr: SignatureBuiltin*}() {
+///     tempvar r;
+///     tempvar s;
+///     %{ sign(ids.private_key, ids.message_hash, ids.r, ids.s) %}
+///     verify_ecdsa_signature(message_hash, public_key, r, s);
+///     ...
+/// }
+/// ```
+pub fn sign(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (private_key, message_hash, r_output, s_output) = match non_empty_args(args)[..] {
+		[private_key, message_hash, r_output, s_output] => (
+			felt_arg(private_key, vm, ids_data, ap_tracking)?,
+			felt_arg(message_hash, vm, ids_data, ap_tracking)?,
+			output_var(r_output)?,
+			output_var(s_output)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"sign(private_key, message_hash, ids.r, ids.s)",
+			)),
+	};
+
+	let (r, s) = sign_message(&private_key, &message_hash)?;
+	insert_value_from_var_name(r_output, r, vm, ids_data, ap_tracking)?;
+	insert_value_from_var_name(s_output, s, vm, ids_data, ap_tracking)
+}
+
+/// Get the keypair of a deterministic test account
+///
+/// The private and public keys are written to the last two cairo variables.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_account{syscall_ptr: felt*}() {
+///     tempvar private_key;
+///     tempvar public_key;
+///     %{ test_account(0, ids.private_key, ids.public_key) %}
+///     ...
+/// }
+/// ```
+pub fn test_account(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (index, private_key_output, public_key_output) = match non_empty_args(args)[..] {
+		[index, private_key_output, public_key_output] => (
+			felt_arg(index, vm, ids_data, ap_tracking)?,
+			output_var(private_key_output)?,
+			output_var(public_key_output)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"test_account(index, ids.private_key, ids.public_key)",
+			)),
+	};
+	let index = index.to_u64().ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid test account index {index}"))
+	})?;
+
+	let key_pair = KeyPair::test_account(index);
+	insert_value_from_var_name(
+		private_key_output,
+		key_pair.private_key,
+		vm,
+		ids_data,
+		ap_tracking,
+	)?;
+	insert_value_from_var_name(
+		public_key_output,
+		key_pair.public_key,
+		vm,
+		ids_data,
+		ap_tracking,
+	)
+}
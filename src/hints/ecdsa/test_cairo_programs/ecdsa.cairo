@@ -0,0 +1,40 @@
+%builtins ecdsa
+
+from starkware.cairo.common.cairo_builtins import SignatureBuiltin
+from starkware.cairo.common.signature import verify_ecdsa_signature
+
+func test_sign_and_verify{ecdsa_ptr: SignatureBuiltin*}() {
+    alloc_locals;
+    local private_key;
+    local public_key;
+    %{ test_account(0, ids.private_key, ids.public_key) %}
+
+    local r;
+    local s;
+    %{ sign(ids.private_key, 0x1234, ids.r, ids.s) %}
+    verify_ecdsa_signature(0x1234, public_key, r, s);
+    return ();
+}
+
+func test_verify_with_wrong_public_key{ecdsa_ptr: SignatureBuiltin*}() {
+    alloc_locals;
+    local private_key;
+    local public_key;
+    %{ test_account(0, ids.private_key, ids.public_key) %}
+    local other_private_key;
+    local other_public_key;
+    %{ test_account(1, ids.other_private_key, ids.other_public_key) %}
+
+    local r;
+    local s;
+    %{ sign(ids.private_key, 0x1234, ids.r, ids.s) %}
+    verify_ecdsa_signature(0x1234, other_public_key, r, s);
+    return ();
+}
+
+func test_sign_wrong_arguments{ecdsa_ptr: SignatureBuiltin*}() {
+    alloc_locals;
+    local r;
+    %{ sign(1, 0x1234, ids.r) %}
+    return ();
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use num_bigint::BigInt;
+use rstest::rstest;
+use starknet_crypto::verify;
+
+use super::{sign_message, KeyPair};
+use crate::{
+	cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus},
+	hints::storage::to_field_element,
+};
+
+#[rstest]
+#[case("test_sign_and_verify", TestStatus::SUCCESS)]
+#[case("test_verify_with_wrong_public_key", TestStatus::FAILURE)]
+#[case("test_sign_wrong_arguments", TestStatus::FAILURE)]
+fn ecdsa(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/ecdsa/test_cairo_programs/ecdsa.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn signatures_are_valid() {
+	let key_pair = KeyPair::test_account(0);
+	let message_hash = BigInt::from(0x1234);
+	let (r, s) = sign_message(&key_pair.private_key, &message_hash).unwrap();
+
+	assert!(verify(
+		&to_field_element(&key_pair.public_key),
+		&to_field_element(&message_hash),
+		&to_field_element(&r),
+		&to_field_element(&s),
+	)
+	.unwrap());
+	// Signing is deterministic
+	assert_eq!(
+		(r, s),
+		sign_message(&key_pair.private_key, &message_hash).unwrap()
+	);
+}
+
+#[test]
+fn test_accounts_are_deterministic() {
+	assert_eq!(KeyPair::test_account(0), KeyPair::test_account(0));
+	assert_ne!(KeyPair::test_account(0), KeyPair::test_account(1));
+}
@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, fmt::Display};
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use serde_json::Value;
+
+use super::{
+	args::{felt_from_json, non_empty_args, wrong_arguments},
+	sn_keccak,
+	syscalls::{get_starknet_state, Event},
+};
+
+pub const EXPECTED_EVENTS_VAR_NAME: &str = "expected_events";
+
+/// An event a test expects to be emitted
+///
+/// Events are identified by their name, whose `sn_keccak` is the first key of the emitted event.
+/// The data and the emitting contract are only compared when given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedEvent {
+	pub name: String,
+	pub data: Option<Vec<BigInt>>,
+	pub from_address: Option<BigInt>,
+}
+
+impl ExpectedEvent {
+	/// Parse an event from its JSON description:
+	/// `{"name": "Transfer", "data": [1, "0x2"], "from_address": 1}`
+	pub fn parse(description: &str) -> Result<Self, VirtualMachineError> {
+		let invalid = |reason: &str| {
+			VirtualMachineError::CustomHint(format!("invalid event {description}: {reason}"))
+		};
+
+		let value: Value =
+			serde_json::from_str(description).map_err(|e| invalid(&e.to_string()))?;
+		let object = value.as_object().ok_or_else(|| invalid("expected an object"))?;
+		if let Some(field) = object
+			.keys()
+			.find(|field| !["name", "data", "from_address"].contains(&field.as_str()))
+		{
+			return Err(invalid(&format!("unknown field '{field}'")))
+		}
+
+		let name = object
+			.get("name")
+			.and_then(Value::as_str)
+			.ok_or_else(|| invalid("missing 'name'"))?
+			.to_string();
+		let data = object
+			.get("data")
+			.map(|data| {
+				data.as_array()
+					.ok_or_else(|| invalid("'data' should be an array"))?
+					.iter()
+					.map(|felt| {
+						felt_from_json(felt).ok_or_else(|| invalid("'data' should be felts"))
+					})
+					.collect::<Result<Vec<_>, _>>()
+			})
+			.transpose()?;
+		let from_address = object
+			.get("from_address")
+			.map(|address| {
+				felt_from_json(address).ok_or_else(|| invalid("'from_address' should be a felt"))
+			})
+			.transpose()?;
+
+		Ok(Self {
+			name,
+			data,
+			from_address,
+		})
+	}
+
+	/// The only key of an event declared with `@event`
+	pub fn key(&self) -> BigInt {
+		sn_keccak(self.name.as_bytes())
+	}
+
+	pub fn matches(&self, event: &Event) -> bool {
+		event.keys == [self.key()]
+			&& self.data.as_ref().map_or(true, |data| *data == event.data)
+			&& self
+				.from_address
+				.as_ref()
+				.map_or(true, |address| *address == event.from_address)
+	}
+}
+
+impl Display for ExpectedEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} keys=[{:#x}]", self.name, self.key())?;
+		if let Some(data) = &self.data {
+			write!(f, " data={}", format_felts(data))?;
+		}
+		if let Some(from_address) = &self.from_address {
+			write!(f, " from={from_address:#x}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Expect events to be emitted, in this order, by the end of the test
+///
+/// Other events may be emitted before, between or after the expected ones.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_transfer{syscall_ptr: felt*}() {
+///     %{ expect_events({"name": "Transfer", "data": [1, 2, 100]}) %}
+///     ...
+/// }
+/// ```
+pub fn expect_events(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let mut expected_events = parse_events(args, "expect_events(event, ...)")?;
+
+	if let Ok(previous) = exec_scopes.get::<Vec<ExpectedEvent>>(EXPECTED_EVENTS_VAR_NAME) {
+		expected_events = previous.into_iter().chain(expected_events).collect();
+	}
+	exec_scopes.assign_or_update_variable(EXPECTED_EVENTS_VAR_NAME, Box::new(expected_events));
+	Ok(())
+}
+
+/// Assert events have already been emitted, in any order
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_transfer{syscall_ptr: felt*}() {
+///     ...
+///     %{ assert_emitted({"name": "Transfer"}, {"name": "Approval", "data": [1, 2, 0]}) %}
+/// }
+/// ```
+pub fn assert_emitted(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let expected_events = parse_events(args, "assert_emitted(event, ...)")?;
+	let emitted_events = &get_starknet_state(exec_scopes)?.events;
+
+	let missing: Vec<_> = expected_events
+		.iter()
+		.filter(|expected| !emitted_events.iter().any(|event| expected.matches(event)))
+		.cloned()
+		.collect();
+	if missing.is_empty() {
+		Ok(())
+	} else {
+		Err(events_error(&expected_events, &missing, emitted_events))
+	}
+}
+
+/// Check the events expected with `expect_events` were emitted during the execution
+pub fn check_expected_events(exec_scopes: &mut ExecutionScopes) -> Result<(), VirtualMachineError> {
+	let expected_events = match exec_scopes.get::<Vec<ExpectedEvent>>(EXPECTED_EVENTS_VAR_NAME) {
+		Ok(expected_events) => expected_events,
+		Err(_) => return Ok(()),
+	};
+	let emitted_events = &get_starknet_state(exec_scopes)?.events;
+
+	let mut remaining = expected_events.iter().peekable();
+	for event in emitted_events {
+		if remaining.peek().map_or(false, |expected| expected.matches(event)) {
+			remaining.next();
+		}
+	}
+
+	let missing: Vec<_> = remaining.cloned().collect();
+	if missing.is_empty() {
+		Ok(())
+	} else {
+		Err(events_error(&expected_events, &missing, emitted_events))
+	}
+}
+
+fn parse_events(
+	args: &[String],
+	expected_signature: &str,
+) -> Result<Vec<ExpectedEvent>, VirtualMachineError> {
+	let args = non_empty_args(args);
+	if args.is_empty() {
+		return Err(wrong_arguments(expected_signature))
+	}
+	args.into_iter().map(ExpectedEvent::parse).collect()
+}
+
+/// Build the error listing the missing events along with the emitted ones
+fn events_error(
+	expected_events: &[ExpectedEvent],
+	missing: &[ExpectedEvent],
+	emitted_events: &[Event],
+) -> VirtualMachineError {
+	let mut message = String::from("expected events were not emitted\nmissing:\n");
+	for event in missing {
+		message.push_str(&format!("  - {event}\n"));
+	}
+
+	message.push_str("emitted:\n");
+	if emitted_events.is_empty() {
+		message.push_str("  (none)\n");
+	}
+	for event in emitted_events {
+		let name = event
+			.keys
+			.first()
+			.and_then(|key| expected_events.iter().find(|expected| expected.key() == *key))
+			.map_or("<unknown>", |expected| expected.name.as_str());
+		message.push_str(&format!(
+			"  + {name} keys=[{}] data={} from={:#x}\n",
+			event.keys.iter().map(|key| format!("{key:#x}")).collect::<Vec<_>>().join(", "),
+			format_felts(&event.data),
+			event.from_address
+		));
+	}
+
+	VirtualMachineError::CustomHint(message.trim_end().to_string())
+}
+
+pub(crate) fn format_felts(felts: &[BigInt]) -> String {
+	format!(
+		"[{}]",
+		felts.iter().map(BigInt::to_string).collect::<Vec<_>>().join(", ")
+	)
+}
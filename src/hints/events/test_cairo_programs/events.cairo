@@ -0,0 +1,44 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+
+@event
+func Transfer(from_: felt, to: felt, amount: felt) {
+}
+
+@event
+func Approval(owner: felt, spender: felt, amount: felt) {
+}
+
+func transfer{syscall_ptr: felt*, range_check_ptr}(to: felt, amount: felt) {
+    Transfer.emit(1, to, amount);
+    return ();
+}
+
+func test_expect_events{syscall_ptr: felt*, range_check_ptr}() {
+    %{ expect_events({"name": "Transfer", "data": [1, 2, 100]}, {"name": "Transfer"}) %}
+    transfer(2, 100);
+    Approval.emit(1, 2, 0);
+    transfer(3, 50);
+    return ();
+}
+
+func test_expect_events_in_wrong_order{syscall_ptr: felt*, range_check_ptr}() {
+    %{ expect_events({"name": "Approval"}, {"name": "Transfer", "data": [1, 2, 100]}) %}
+    transfer(2, 100);
+    Approval.emit(1, 2, 0);
+    return ();
+}
+
+func test_assert_emitted{syscall_ptr: felt*, range_check_ptr}() {
+    transfer(2, 100);
+    Approval.emit(1, 2, 0);
+    %{ assert_emitted({"name": "Approval", "from_address": 1}, {"name": "Transfer", "data": ["1", "0x2", 100]}) %}
+    return ();
+}
+
+func test_assert_emitted_with_wrong_data{syscall_ptr: felt*, range_check_ptr}() {
+    transfer(2, 100);
+    %{ assert_emitted({"name": "Transfer", "data": [1, 2, 99]}) %}
+    return ();
+}
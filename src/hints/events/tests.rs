@@ -0,0 +1,64 @@
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use super::ExpectedEvent;
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+const EVENTS_PROGRAM: &str = "src/hints/events/test_cairo_programs/events.cairo";
+
+#[test]
+fn parse_expected_event() {
+	assert_eq!(
+		ExpectedEvent {
+			name: String::from("Transfer"),
+			data: Some(vec![BigInt::from(1), BigInt::from(2), BigInt::from(100)]),
+			from_address: None,
+		},
+		ExpectedEvent::parse(r#"{"name": "Transfer", "data": [1, "0x2", "100"]}"#).unwrap()
+	);
+	assert!(ExpectedEvent::parse(r#"{"data": [1]}"#).is_err());
+	assert!(ExpectedEvent::parse(r#"{"name": "Transfer", "keys": [1]}"#).is_err());
+}
+
+#[rstest]
+#[case("test_expect_events", TestStatus::SUCCESS)]
+#[case("test_expect_events_in_wrong_order", TestStatus::FAILURE)]
+#[case("test_assert_emitted", TestStatus::SUCCESS)]
+#[case("test_assert_emitted_with_wrong_data", TestStatus::FAILURE)]
+fn events(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from(EVENTS_PROGRAM);
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn failure_shows_missing_and_emitted_events() -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from(EVENTS_PROGRAM);
+	let result = run_single_test("test_assert_emitted_with_wrong_data", &path, 1000000)?;
+
+	assert!(
+		result.output.contains("  - Transfer keys=[0x"),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("data=[1, 2, 99]"),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("  + Transfer keys=[0x"),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("data=[1, 2, 100] from=0x1"),
+		"{}",
+		result.output
+	);
+	Ok(())
+}
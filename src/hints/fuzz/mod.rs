@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::{ApTracking, HintParams},
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::args::{
+	felt_arg, felt_list_arg, felt_literal, function_hint_args, literal_list, non_empty_args,
+	string_arg, wrong_arguments,
+};
+use crate::fuzz::FuzzRange;
+
+pub const ASSUME_REJECTED_FLAG: &str = "assume_rejected";
+
+/// Comparison operators supported by `assume`, two-character ones first so that `<=` is not
+/// read as `<`
+const COMPARISON_OPERATORS: [&str; 6] = ["<=", ">=", "==", "!=", "<", ">"];
+
+/// Discard the current input of a fuzzed test when a condition does not hold
+///
+/// The execution is aborted and the test is run again with new inputs, without the run
+/// being counted. Operands are felt arguments, compared as integers in `[0, P)`.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_deposit(amount: felt) {
+///     %{ assume(ids.amount < 2**128) %}
+///     ...
+/// }
+/// ```
+pub fn assume(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let condition = match non_empty_args(args)[..] {
+		[condition] => condition,
+		_ => return Err(wrong_arguments("assume(lhs <op> rhs)")),
+	};
+	let (lhs, operator, rhs) = COMPARISON_OPERATORS
+		.iter()
+		.find_map(|operator| condition.split_once(operator).map(|(lhs, rhs)| (lhs, *operator, rhs)))
+		.ok_or_else(|| wrong_arguments("assume(lhs <op> rhs)"))?;
+	let lhs = felt_arg(lhs.trim(), vm, ids_data, ap_tracking)?;
+	let rhs = felt_arg(rhs.trim(), vm, ids_data, ap_tracking)?;
+
+	let holds = match operator {
+		"<=" => lhs <= rhs,
+		">=" => lhs >= rhs,
+		"==" => lhs == rhs,
+		"!=" => lhs != rhs,
+		"<" => lhs < rhs,
+		_ => lhs > rhs,
+	};
+	if holds {
+		return Ok(())
+	}
+	Err(VirtualMachineError::CustomHint(
+		ASSUME_REJECTED_FLAG.to_string(),
+	))
+}
+
+/// Bound the inputs generated for an argument of a fuzzed test
+///
+/// Bounds are inclusive. The fuzzer reads them from the code of the test before running it,
+/// so they must be literals. During the execution, inputs out of the bounds (replayed from the
+/// corpus or tried while shrinking) are discarded as with `assume`.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_deposit(amount: felt) {
+///     %{ fuzz_range("amount", 0, 1000) %}
+///     ...
+/// }
+/// ```
+pub fn fuzz_range(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (name, (low, high)) = parse_fuzz_range(args)
+		.ok_or_else(|| wrong_arguments("fuzz_range(\"argument\", low, high)"))?;
+
+	let value = felt_arg(&format!("ids.{name}"), vm, ids_data, ap_tracking)?;
+	if low <= value && value <= high {
+		return Ok(())
+	}
+	Err(VirtualMachineError::CustomHint(
+		ASSUME_REJECTED_FLAG.to_string(),
+	))
+}
+
+/// Set the contract whose external functions an invariant test calls
+///
+/// The contract is compiled and deployed with the given constructor calldata before the calls
+/// are made. Like `fuzz_range`, the hint is read from the code of the test before running it,
+/// so its arguments must be literals. The invariant gets the address of the contract as
+/// argument, if it takes one.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func invariant_reserves{syscall_ptr: felt*, range_check_ptr}(amm: felt) {
+///     %{ target_contract("contracts/amm.cairo", [1000, 1000]) %}
+///     ...
+/// }
+/// ```
+pub fn target_contract(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	match non_empty_args(args)[..] {
+		[path] => string_arg(path).map(|_| ()),
+		[path, calldata] => {
+			string_arg(path)?;
+			felt_list_arg(calldata, vm, ids_data, ap_tracking).map(|_| ())
+		},
+		_ => Err(wrong_arguments("target_contract(path, [calldata...]?)")),
+	}
+}
+
+/// Returns the bounds given by the `fuzz_range` hints of a function, by argument name
+pub fn fuzz_ranges(
+	hints: &HashMap<usize, Vec<HintParams>>,
+	function: &str,
+) -> HashMap<String, FuzzRange> {
+	function_hint_args(hints, function, "fuzz_range")
+		.filter_map(|args| parse_fuzz_range(&args))
+		.collect()
+}
+
+/// Returns the path and constructor calldata given by the `target_contract` hint of a function
+pub fn invariant_target(
+	hints: &HashMap<usize, Vec<HintParams>>,
+	function: &str,
+) -> Option<(PathBuf, Vec<BigInt>)> {
+	function_hint_args(hints, function, "target_contract").find_map(|args| {
+		let (path, calldata) = match non_empty_args(&args)[..] {
+			[path] => (path, None),
+			[path, calldata] => (path, Some(calldata)),
+			_ => return None,
+		};
+		let calldata = match calldata {
+			Some(calldata) => literal_list(calldata)?,
+			None => Vec::new(),
+		};
+		Some((PathBuf::from(string_arg(path).ok()?), calldata))
+	})
+}
+
+/// Returns the argument name and bounds of a `fuzz_range` hint, if they are valid literals
+fn parse_fuzz_range(args: &[String]) -> Option<(String, FuzzRange)> {
+	match non_empty_args(args)[..] {
+		[name, low, high] => {
+			let name = string_arg(name).ok()?;
+			let low = felt_literal(low.trim())?;
+			let high = felt_literal(high.trim())?;
+			(BigInt::from(0) <= low && low <= high).then(|| (name.to_string(), (low, high)))
+		},
+		_ => None,
+	}
+}
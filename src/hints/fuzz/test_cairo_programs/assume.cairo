@@ -0,0 +1,44 @@
+%builtins range_check
+
+from starkware.cairo.common.math import assert_in_range, assert_le_felt, assert_not_zero
+
+func test_assume{range_check_ptr}(amount: felt) {
+    %{ assume(ids.amount < 2**128) %}
+    assert_le_felt(amount, 2 ** 128);
+    return ();
+}
+
+func test_assume_not_zero(value: felt) {
+    %{ assume(ids.value != 0) %}
+    assert_not_zero(value);
+    return ();
+}
+
+func test_fuzz_range{range_check_ptr}(amount: felt, other: felt) {
+    %{ fuzz_range("amount", 10, 1000) %}
+    assert_in_range(amount, 10, 1001);
+    return ();
+}
+
+func test_fuzz_range_failing{range_check_ptr}(amount: felt) {
+    %{ fuzz_range("amount", 0, 2**64) %}
+    assert_in_range(amount, 0, 2 ** 32);
+    return ();
+}
+
+func test_assume_never(value: felt) {
+    %{ assume(ids.value != ids.value) %}
+    return ();
+}
+
+// -1 is read as P - 1, the value the contract computes
+func test_assume_minus_one() {
+    let value = -1;
+    %{ assume(ids.value == -1) %}
+    return ();
+}
+
+func test_assume_without_arguments() {
+    %{ assume(1 > 2) %}
+    return ();
+}
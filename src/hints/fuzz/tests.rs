@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_assume", TestStatus::SUCCESS)]
+#[case("test_assume_not_zero", TestStatus::SUCCESS)]
+#[case("test_fuzz_range", TestStatus::SUCCESS)]
+#[case("test_fuzz_range_failing", TestStatus::FAILURE)]
+#[case("test_assume_never", TestStatus::FAILURE)]
+#[case("test_assume_minus_one", TestStatus::SUCCESS)]
+#[case("test_assume_without_arguments", TestStatus::FAILURE)]
+fn assume(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/fuzz/test_cairo_programs/assume.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn too_many_rejected_inputs_are_reported() {
+	let path = PathBuf::from("src/hints/fuzz/test_cairo_programs/assume.cairo");
+	let result = run_single_test("test_assume_never", &path, 1000000).unwrap();
+
+	assert!(
+		result.output.contains("too many inputs rejected"),
+		"{}",
+		result.output
+	);
+}
@@ -136,10 +136,9 @@ impl HintProcessor for FunctionLikeHintProcessor {
 			}))
 		}
 		let name_func = trimmed_hint_code[..index_of_opening_parenthesis].to_string();
-		let list_args = trimmed_hint_code[index_of_opening_parenthesis + 1..]
-			.split(',')
-			.map(|x| x.trim().trim_matches(')').to_string())
-			.collect();
+		let list_args = split_args(
+			&trimmed_hint_code[index_of_opening_parenthesis + 1..trimmed_hint_code.len() - 1],
+		);
 
 		Ok(any_box!(FunctionLikeHintProcessorData {
 			code: Code::Function(name_func, list_args),
@@ -149,6 +148,31 @@ impl HintProcessor for FunctionLikeHintProcessor {
 	}
 }
 
+/// Split the arguments of a function-like hint on top-level commas only, so that arguments
+/// can be lists, objects or strings containing commas
+pub(crate) fn split_args(args: &str) -> Vec<String> {
+	let mut list_args = Vec::new();
+	let mut depth = 0;
+	let mut quote = None;
+	let mut start = 0;
+	for (i, c) in args.char_indices() {
+		match (quote, c) {
+			(Some(q), c) if c == q => quote = None,
+			(Some(_), _) => {},
+			(None, '"' | '\'') => quote = Some(c),
+			(None, '(' | '[' | '{') => depth += 1,
+			(None, ')' | ']' | '}') => depth -= 1,
+			(None, ',') if depth == 0 => {
+				list_args.push(args[start..i].trim().to_string());
+				start = i + 1;
+			},
+			_ => {},
+		}
+	}
+	list_args.push(args[start..].trim().to_string());
+	list_args
+}
+
 fn get_ids_data(
 	reference_ids: &HashMap<String, usize>,
 	references: &HashMap<usize, HintReference>,
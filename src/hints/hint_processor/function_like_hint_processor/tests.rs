@@ -107,3 +107,38 @@ fn test_function_like_hint_should_return_function_name_and_args_hint(
 	);
 	Ok(())
 }
+
+#[rstest]
+#[case("expect_events()", vec![""])]
+#[case(
+	"expect_events({\"name\": \"Transfer\", \"data\": [1, 2]}, 'a, b')",
+	vec!["{\"name\": \"Transfer\", \"data\": [1, 2]}", "'a, b'"]
+)]
+fn test_function_like_hint_should_split_args_on_top_level_commas(
+	#[case] hint_code: &str,
+	#[case] expected_args: Vec<&str>,
+) -> Result<(), VirtualMachineError> {
+	let hint_processor = FunctionLikeHintProcessor::default();
+
+	let hint_data = hint_processor
+		.compile_hint(
+			hint_code,
+			&Default::default(),
+			&Default::default(),
+			&Default::default(),
+		)
+		.unwrap();
+
+	let hint_data_return = hint_data
+		.downcast_ref::<FunctionLikeHintProcessorData>()
+		.ok_or(VirtualMachineError::WrongHintData)?;
+
+	assert_eq!(
+		Code::Function(
+			String::from("expect_events"),
+			expected_args.into_iter().map(String::from).collect()
+		),
+		hint_data_return.code
+	);
+	Ok(())
+}
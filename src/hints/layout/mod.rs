@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::{ApTracking, HintParams},
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::args::{function_hint_args, non_empty_args, string_arg, wrong_arguments};
+
+/// Run a test with the given layout, whatever the layout of the rest of the suite
+///
+/// Tests using the `bitwise`, `ec_op` or `keccak` builtins need a layout providing them, such
+/// as `all`. The hint is read from the code of the test before running it, so the layout must
+/// be a literal.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_xor{bitwise_ptr: BitwiseBuiltin*}() {
+///     %{ layout("all") %}
+///     ...
+/// }
+/// ```
+pub fn layout(
+	_vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	match non_empty_args(args)[..] {
+		[layout] => string_arg(layout).map(|_| ()),
+		_ => Err(wrong_arguments("layout(name)")),
+	}
+}
+
+/// Returns the layout given by the `layout` hint of a function, if it has one
+pub fn test_layout(hints: &HashMap<usize, Vec<HintParams>>, function: &str) -> Option<String> {
+	function_hint_args(hints, function, "layout").find_map(|args| match non_empty_args(&args)[..] {
+		[layout] => string_arg(layout).ok().map(String::from),
+		_ => None,
+	})
+}
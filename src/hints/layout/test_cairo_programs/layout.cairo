@@ -0,0 +1,26 @@
+from starkware.cairo.common.bitwise import bitwise_xor
+from starkware.cairo.common.cairo_builtins import BitwiseBuiltin
+
+func test_bitwise_with_all_layout{bitwise_ptr: BitwiseBuiltin*}() {
+    %{ layout("all") %}
+    let (result) = bitwise_xor(12, 10);
+    assert result = 6;
+    return ();
+}
+
+// The default layout has no bitwise builtin
+func test_bitwise_with_default_layout{bitwise_ptr: BitwiseBuiltin*}() {
+    let (result) = bitwise_xor(12, 10);
+    assert result = 6;
+    return ();
+}
+
+func test_unknown_layout() {
+    %{ layout("unknown") %}
+    return ();
+}
+
+func test_layout_without_arguments() {
+    %{ layout() %}
+    return ();
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_bitwise_with_all_layout", TestStatus::SUCCESS)]
+#[case("test_bitwise_with_default_layout", TestStatus::FAILURE)]
+#[case("test_unknown_layout", TestStatus::FAILURE)]
+#[case("test_layout_without_arguments", TestStatus::FAILURE)]
+fn layout(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/layout/test_cairo_programs/layout.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::{
+	args::{felt_arg, felt_list_arg, non_empty_args, string_arg, wrong_arguments},
+	contracts,
+	events::format_felts,
+	sn_keccak,
+	syscalls::{get_starknet_state, MessageToL1},
+};
+
+pub const EXPECTED_MESSAGES_TO_L1_VAR_NAME: &str = "expected_messages_to_l1";
+
+/// A message to L1 a test expects to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedMessageToL1 {
+	pub to_address: BigInt,
+	pub payload: Vec<BigInt>,
+}
+
+impl ExpectedMessageToL1 {
+	pub fn matches(&self, message: &MessageToL1) -> bool {
+		self.to_address == message.to_address && self.payload == message.payload
+	}
+}
+
+/// Expect a message to be sent to L1 by the end of the test
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_withdraw{syscall_ptr: felt*}() {
+///     %{ expect_message_to_l1(ids.L1_BRIDGE, [ids.account, 100]) %}
+///     ...
+/// }
+/// ```
+pub fn expect_message_to_l1(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let expected_message = match non_empty_args(args)[..] {
+		[to_address, payload] => ExpectedMessageToL1 {
+			to_address: felt_arg(to_address, vm, ids_data, ap_tracking)?,
+			payload: felt_list_arg(payload, vm, ids_data, ap_tracking)?,
+		},
+		_ =>
+			return Err(wrong_arguments(
+				"expect_message_to_l1(to_address, [payload...])",
+			)),
+	};
+
+	let mut expected_messages = exec_scopes
+		.get::<Vec<ExpectedMessageToL1>>(EXPECTED_MESSAGES_TO_L1_VAR_NAME)
+		.unwrap_or_default();
+	expected_messages.push(expected_message);
+	exec_scopes.assign_or_update_variable(
+		EXPECTED_MESSAGES_TO_L1_VAR_NAME,
+		Box::new(expected_messages),
+	);
+	Ok(())
+}
+
+/// Check the messages expected with `expect_message_to_l1` were sent during the execution
+///
+/// Messages may be sent in any order, but each one only matches a single expected message.
+pub fn check_expected_messages_to_l1(
+	exec_scopes: &mut ExecutionScopes,
+) -> Result<(), VirtualMachineError> {
+	let expected_messages =
+		match exec_scopes.get::<Vec<ExpectedMessageToL1>>(EXPECTED_MESSAGES_TO_L1_VAR_NAME) {
+			Ok(expected_messages) => expected_messages,
+			Err(_) => return Ok(()),
+		};
+	let sent_messages = &get_starknet_state(exec_scopes)?.messages_to_l1;
+
+	let mut unmatched: Vec<&MessageToL1> = sent_messages.iter().collect();
+	let mut missing = Vec::new();
+	for expected in &expected_messages {
+		match unmatched.iter().position(|message| expected.matches(message)) {
+			Some(position) => {
+				unmatched.remove(position);
+			},
+			None => missing.push(expected),
+		}
+	}
+	if missing.is_empty() {
+		return Ok(())
+	}
+
+	let mut error = String::from("expected messages to L1 were not sent\nmissing:\n");
+	for expected in missing {
+		error.push_str(&format!(
+			"  - to={:#x} payload={}\n",
+			expected.to_address,
+			format_felts(&expected.payload)
+		));
+	}
+	error.push_str("sent:\n");
+	if sent_messages.is_empty() {
+		error.push_str("  (none)\n");
+	}
+	for sent in sent_messages {
+		error.push_str(&format!(
+			"  + to={:#x} payload={} from={:#x}\n",
+			sent.to_address,
+			format_felts(&sent.payload),
+			sent.from_address
+		));
+	}
+	Err(VirtualMachineError::CustomHint(
+		error.trim_end().to_string(),
+	))
+}
+
+/// Deliver a message from L1 to a deployed contract, invoking one of its `@l1_handler`
+///
+/// The handler is given either by name or by selector. The L1 sender defaults to zero.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_deposit{syscall_ptr: felt*}() {
+///     ...
+///     %{ send_message_to_l2(ids.bridge_address, "deposit", [ids.account, 100], from_address=ids.L1_BRIDGE) %}
+///     ...
+/// }
+/// ```
+pub fn send_message_to_l2(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (contract_address, selector, payload, from_address) = match non_empty_args(args)[..] {
+		[contract_address, selector, payload] => (
+			felt_arg(contract_address, vm, ids_data, ap_tracking)?,
+			selector_arg(selector, vm, ids_data, ap_tracking)?,
+			felt_list_arg(payload, vm, ids_data, ap_tracking)?,
+			BigInt::from(0),
+		),
+		[contract_address, selector, payload, from_address] => (
+			felt_arg(contract_address, vm, ids_data, ap_tracking)?,
+			selector_arg(selector, vm, ids_data, ap_tracking)?,
+			felt_list_arg(payload, vm, ids_data, ap_tracking)?,
+			felt_arg(from_address, vm, ids_data, ap_tracking)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"send_message_to_l2(contract_address, selector, [payload...], from_address?)",
+			)),
+	};
+
+	contracts::l1_handler(
+		exec_scopes,
+		*vm.get_current_step(),
+		&contract_address,
+		&selector,
+		&from_address,
+		&payload,
+	)?;
+	Ok(())
+}
+
+/// Returns the selector of an entry point given by name (`"deposit"`) or by selector
+fn selector_arg(
+	arg: &str,
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<BigInt, VirtualMachineError> {
+	match string_arg(arg) {
+		Ok(name) => Ok(sn_keccak(name.as_bytes())),
+		Err(_) => felt_arg(arg, vm, ids_data, ap_tracking),
+	}
+}
@@ -0,0 +1,43 @@
+%lang starknet
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.starknet.common.messages import send_message_to_l1
+
+const L1_BRIDGE = 0xb41d6e;
+
+@storage_var
+func balances(account: felt) -> (balance: felt) {
+}
+
+@l1_handler
+func deposit{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    from_address: felt, account: felt, amount: felt
+) {
+    assert from_address = L1_BRIDGE;
+    let (balance) = balances.read(account);
+    balances.write(account, balance + amount);
+    return ();
+}
+
+@external
+func withdraw{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    account: felt, amount: felt
+) {
+    let (balance) = balances.read(account);
+    balances.write(account, balance - amount);
+
+    let (payload: felt*) = alloc();
+    assert payload[0] = account;
+    assert payload[1] = amount;
+    send_message_to_l1(to_address=L1_BRIDGE, payload_size=2, payload=payload);
+    return ();
+}
+
+@view
+func balance_of{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    account: felt
+) -> (balance: felt) {
+    let (balance) = balances.read(account);
+    return (balance=balance);
+}
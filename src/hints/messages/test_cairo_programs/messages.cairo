@@ -0,0 +1,58 @@
+%lang starknet
+
+@contract_interface
+namespace IBridge {
+    func withdraw(account: felt, amount: felt) {
+    }
+
+    func balance_of(account: felt) -> (balance: felt) {
+    }
+}
+
+func deploy_bridge() -> (bridge_address: felt) {
+    alloc_locals;
+    local class_hash;
+    local bridge_address;
+    %{ declare("src/hints/messages/test_cairo_programs/bridge.cairo", ids.class_hash) %}
+    %{ deploy(ids.class_hash, [], ids.bridge_address) %}
+    return (bridge_address=bridge_address);
+}
+
+func test_deposit{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    let (local bridge_address) = deploy_bridge();
+    %{ send_message_to_l2(ids.bridge_address, "deposit", [0x123, 100], from_address=0xb41d6e) %}
+
+    let (balance) = IBridge.balance_of(bridge_address, 0x123);
+    assert 100 = balance;
+    return ();
+}
+
+func test_deposit_from_unknown_l1_address{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    let (local bridge_address) = deploy_bridge();
+    %{ send_message_to_l2(ids.bridge_address, "deposit", [0x123, 100]) %}
+    return ();
+}
+
+func test_withdraw{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    let (local bridge_address) = deploy_bridge();
+    %{ send_message_to_l2(ids.bridge_address, "deposit", [0x123, 100], from_address=0xb41d6e) %}
+    %{ expect_message_to_l1(0xb41d6e, [0x123, 40]) %}
+
+    IBridge.withdraw(bridge_address, 0x123, 40);
+    let (balance) = IBridge.balance_of(bridge_address, 0x123);
+    assert 60 = balance;
+    return ();
+}
+
+func test_message_to_l1_not_sent{syscall_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    let (local bridge_address) = deploy_bridge();
+    %{ send_message_to_l2(ids.bridge_address, "deposit", [0x123, 100], from_address=0xb41d6e) %}
+    %{ expect_message_to_l1(0xb41d6e, [0x123, 50]) %}
+
+    IBridge.withdraw(bridge_address, 0x123, 40);
+    return ();
+}
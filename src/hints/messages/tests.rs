@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_deposit", TestStatus::SUCCESS)]
+#[case("test_deposit_from_unknown_l1_address", TestStatus::FAILURE)]
+#[case("test_withdraw", TestStatus::SUCCESS)]
+#[case("test_message_to_l1_not_sent", TestStatus::FAILURE)]
+fn messages(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/messages/test_cairo_programs/messages.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn missing_messages_are_listed() {
+	let path = PathBuf::from("src/hints/messages/test_cairo_programs/messages.cairo");
+	let result = run_single_test("test_message_to_l1_not_sent", &path, 1000000).unwrap();
+
+	assert!(
+		result.output.contains("  - to=0xb41d6e payload=[291, 50]"),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("  + to=0xb41d6e payload=[291, 40] from=0x2"),
+		"{}",
+		result.output
+	);
+}
@@ -7,6 +7,39 @@ pub use expect_revert::{expect_revert, EXPECT_REVERT_FLAG};
 mod skip;
 pub use skip::*;
 
+mod fuzz;
+pub use fuzz::*;
+
+mod parametrize;
+pub use parametrize::*;
+
+mod layout;
+pub use layout::*;
+
+mod cheatcodes;
+pub use cheatcodes::*;
+
+mod storage;
+pub use storage::*;
+
+mod events;
+pub use events::*;
+
+pub mod contracts;
+pub use contracts::{declare, deploy};
+
+mod state_file;
+pub use state_file::*;
+
+mod ecdsa;
+pub use ecdsa::*;
+
+mod messages;
+pub use messages::*;
+
+mod args;
+
 pub(crate) mod hint_processor;
 pub mod output_buffer;
 pub mod processor;
+pub mod syscalls;
@@ -59,7 +59,6 @@ pub fn get_buffer(execution_uuid: &Uuid) -> Option<String> {
 /// Append string `data` to the value of key `execution_uuid` in the HINT_OUTPUT_BUFFER HashMap.
 ///
 /// The given `execution_uuid` is the one used to identify the cairo test entrypoint
-#[allow(dead_code)]
 pub fn write_to_output_buffer(execution_uuid: &Uuid, data: &str) {
 	let mut hashmap_lock = HINT_OUTPUT_BUFFER.write().unwrap();
 	let opt_buffer = hashmap_lock.get_mut(execution_uuid);
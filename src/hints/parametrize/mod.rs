@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::{ApTracking, HintParams},
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::{
+	args::{function_hint_args, literal_list, non_empty_args, wrong_arguments},
+	hint_processor::function_like_hint_processor::split_args,
+};
+
+/// Run a test taking arguments once per row of a table, instead of with random inputs
+///
+/// Each row holds the felts the arguments of the test are set to, struct arguments taking as
+/// many felts as their size. Rows are reported as separate test cases, named after their
+/// number (`test_add[1]`, `test_add[2]`, ...). The hint is read from the code of the test before
+/// running it, so rows must be literals.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_add(a: felt, b: felt, sum: felt) {
+///     %{ parametrize([[1, 2, 3], [4, 5, 9]]) %}
+///     assert sum = a + b;
+///     return ();
+/// }
+/// ```
+pub fn parametrize(
+	_vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	parse_rows(args)
+		.map(|_| ())
+		.ok_or_else(|| wrong_arguments("parametrize([[args...], ...])"))
+}
+
+/// Returns the rows given by the `parametrize` hint of a function, if it has one
+pub fn parametrize_rows(
+	hints: &HashMap<usize, Vec<HintParams>>,
+	function: &str,
+) -> Option<Vec<Vec<BigInt>>> {
+	function_hint_args(hints, function, "parametrize").find_map(|args| parse_rows(&args))
+}
+
+fn parse_rows(args: &[String]) -> Option<Vec<Vec<BigInt>>> {
+	let table = match non_empty_args(args)[..] {
+		[table] => table,
+		_ => return None,
+	};
+	let rows = table.trim().strip_prefix('[')?.strip_suffix(']')?;
+	split_args(rows)
+		.iter()
+		.filter(|row| !row.is_empty())
+		.map(|row| literal_list(row))
+		.collect()
+}
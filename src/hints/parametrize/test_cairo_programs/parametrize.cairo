@@ -0,0 +1,34 @@
+struct Point {
+    x: felt,
+    y: felt,
+}
+
+func test_add(a: felt, b: felt, sum: felt) {
+    %{ parametrize([[1, 2, 3], [4, 5, 9], [2**128, 0, 2**128]]) %}
+    assert sum = a + b;
+    return ();
+}
+
+func test_add_wrong_row(a: felt, b: felt, sum: felt) {
+    %{ parametrize([[1, 2, 3], [4, 5, 10]]) %}
+    assert sum = a + b;
+    return ();
+}
+
+func test_row_of_wrong_size(a: felt, b: felt) {
+    %{ parametrize([[1, 2], [3]]) %}
+    return ();
+}
+
+func test_struct_argument(point: Point, norm: felt) {
+    %{ parametrize([[3, 4, 25], [0, 1, 1]]) %}
+    assert norm = point.x * point.x + point.y * point.y;
+    return ();
+}
+
+// Negative literals are reduced modulo P
+func test_negative_row(a: felt, b: felt) {
+    %{ parametrize([[-1, 1], [-2**128, 2**128]]) %}
+    assert a + b = 0;
+    return ();
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_add", TestStatus::SUCCESS)]
+#[case("test_add_wrong_row", TestStatus::FAILURE)]
+#[case("test_row_of_wrong_size", TestStatus::FAILURE)]
+#[case("test_struct_argument", TestStatus::SUCCESS)]
+#[case("test_negative_row", TestStatus::SUCCESS)]
+fn parametrize(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/parametrize/test_cairo_programs/parametrize.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn rows_are_reported_as_test_cases() {
+	let path = PathBuf::from("src/hints/parametrize/test_cairo_programs/parametrize.cairo");
+	let result = run_single_test("test_add_wrong_row", &path, 1000000).unwrap();
+
+	assert!(
+		result.output.contains("] test_add_wrong_row[1] ("),
+		"{}",
+		result.output
+	);
+	assert!(
+		result.output.contains("] test_add_wrong_row[2]\nError:"),
+		"{}",
+		result.output
+	);
+	let cases = result
+		.cases
+		.iter()
+		.map(|case| (case.name.as_str(), case.success))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		vec![
+			("test_add_wrong_row[1]", TestStatus::SUCCESS),
+			("test_add_wrong_row[2]", TestStatus::FAILURE)
+		],
+		cases
+	);
+}
@@ -1,8 +1,9 @@
 use std::rc::Rc;
 
-use crate::{
-	hints,
-	hints::hint_processor::function_like_hint_processor::{FunctionLikeHintProcessor, HintFunc},
+use crate::hints::{
+	self,
+	hint_processor::function_like_hint_processor::{FunctionLikeHintProcessor, HintFunc},
+	syscalls,
 };
 
 /// Create, setup and return a HintProcessor supporting our custom hints
@@ -10,9 +11,120 @@ pub fn setup_hint_processor() -> FunctionLikeHintProcessor {
 	let skip_hint = Rc::new(HintFunc(Box::new(hints::skip)));
 	let mock_call_hint = Rc::new(HintFunc(Box::new(hints::mock_call)));
 	let expect_revert_hint = Rc::new(HintFunc(Box::new(hints::expect_revert)));
+	let assume_hint = Rc::new(HintFunc(Box::new(hints::assume)));
+	let fuzz_range_hint = Rc::new(HintFunc(Box::new(hints::fuzz_range)));
+	let target_contract_hint = Rc::new(HintFunc(Box::new(hints::target_contract)));
+	let parametrize_hint = Rc::new(HintFunc(Box::new(hints::parametrize)));
+	let layout_hint = Rc::new(HintFunc(Box::new(hints::layout)));
 	let mut hint_processor = FunctionLikeHintProcessor::new_empty();
 	hint_processor.add_hint(String::from("skip"), skip_hint);
 	hint_processor.add_hint(String::from("expect_revert"), expect_revert_hint);
 	hint_processor.add_hint(String::from("mock_call"), mock_call_hint);
+	hint_processor.add_hint(String::from("assume"), assume_hint);
+	hint_processor.add_hint(String::from("fuzz_range"), fuzz_range_hint);
+	hint_processor.add_hint(String::from("target_contract"), target_contract_hint);
+	hint_processor.add_hint(String::from("parametrize"), parametrize_hint);
+	hint_processor.add_hint(String::from("layout"), layout_hint);
+	setup_cheatcodes(&mut hint_processor);
+	setup_syscall_hints(&mut hint_processor);
 	hint_processor
 }
+
+/// Register the hints changing the Starknet context and state of a test
+fn setup_cheatcodes(hint_processor: &mut FunctionLikeHintProcessor) {
+	let start_prank_hint = Rc::new(HintFunc(Box::new(hints::start_prank)));
+	let stop_prank_hint = Rc::new(HintFunc(Box::new(hints::stop_prank)));
+	let warp_hint = Rc::new(HintFunc(Box::new(hints::warp)));
+	let roll_hint = Rc::new(HintFunc(Box::new(hints::roll)));
+	let store_hint = Rc::new(HintFunc(Box::new(hints::store)));
+	let load_hint = Rc::new(HintFunc(Box::new(hints::load)));
+	let expect_events_hint = Rc::new(HintFunc(Box::new(hints::expect_events)));
+	let assert_emitted_hint = Rc::new(HintFunc(Box::new(hints::assert_emitted)));
+	let declare_hint = Rc::new(HintFunc(Box::new(hints::declare)));
+	let deploy_hint = Rc::new(HintFunc(Box::new(hints::deploy)));
+	let load_state_hint = Rc::new(HintFunc(Box::new(hints::load_state)));
+	let sign_hint = Rc::new(HintFunc(Box::new(hints::sign)));
+	let test_account_hint = Rc::new(HintFunc(Box::new(hints::test_account)));
+	let expect_message_to_l1_hint = Rc::new(HintFunc(Box::new(hints::expect_message_to_l1)));
+	let send_message_to_l2_hint = Rc::new(HintFunc(Box::new(hints::send_message_to_l2)));
+	hint_processor.add_hint(String::from("start_prank"), start_prank_hint);
+	hint_processor.add_hint(String::from("stop_prank"), stop_prank_hint);
+	hint_processor.add_hint(String::from("warp"), warp_hint);
+	hint_processor.add_hint(String::from("roll"), roll_hint);
+	hint_processor.add_hint(String::from("store"), store_hint);
+	hint_processor.add_hint(String::from("load"), load_hint);
+	hint_processor.add_hint(String::from("expect_events"), expect_events_hint);
+	hint_processor.add_hint(String::from("assert_emitted"), assert_emitted_hint);
+	hint_processor.add_hint(String::from("declare"), declare_hint);
+	hint_processor.add_hint(String::from("deploy"), deploy_hint);
+	hint_processor.add_hint(String::from("load_state"), load_state_hint);
+	hint_processor.add_hint(String::from("sign"), sign_hint);
+	hint_processor.add_hint(String::from("test_account"), test_account_hint);
+	hint_processor.add_hint(
+		String::from("expect_message_to_l1"),
+		expect_message_to_l1_hint,
+	);
+	hint_processor.add_hint(String::from("send_message_to_l2"), send_message_to_l2_hint);
+}
+
+/// Register the hints emitted by `starkware.starknet.common.syscalls`,
+/// served from the local `StarknetState`
+fn setup_syscall_hints(hint_processor: &mut FunctionLikeHintProcessor) {
+	let storage_read_hint = Rc::new(HintFunc(Box::new(syscalls::storage_read)));
+	let storage_write_hint = Rc::new(HintFunc(Box::new(syscalls::storage_write)));
+	let emit_event_hint = Rc::new(HintFunc(Box::new(syscalls::emit_event)));
+	let send_message_to_l1_hint = Rc::new(HintFunc(Box::new(syscalls::send_message_to_l1)));
+	let get_caller_address_hint = Rc::new(HintFunc(Box::new(syscalls::get_caller_address)));
+	let get_contract_address_hint = Rc::new(HintFunc(Box::new(syscalls::get_contract_address)));
+	let get_sequencer_address_hint = Rc::new(HintFunc(Box::new(syscalls::get_sequencer_address)));
+	let get_block_number_hint = Rc::new(HintFunc(Box::new(syscalls::get_block_number)));
+	let get_block_timestamp_hint = Rc::new(HintFunc(Box::new(syscalls::get_block_timestamp)));
+	let get_tx_info_hint = Rc::new(HintFunc(Box::new(syscalls::get_tx_info)));
+	let call_contract_hint = Rc::new(HintFunc(Box::new(syscalls::call_contract)));
+	let library_call_hint = Rc::new(HintFunc(Box::new(syscalls::library_call)));
+	hint_processor.add_hint(
+		String::from("syscall_handler.storage_read"),
+		storage_read_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.storage_write"),
+		storage_write_hint,
+	);
+	hint_processor.add_hint(String::from("syscall_handler.emit_event"), emit_event_hint);
+	hint_processor.add_hint(
+		String::from("syscall_handler.send_message_to_l1"),
+		send_message_to_l1_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_caller_address"),
+		get_caller_address_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_contract_address"),
+		get_contract_address_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_sequencer_address"),
+		get_sequencer_address_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_block_number"),
+		get_block_number_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_block_timestamp"),
+		get_block_timestamp_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.get_tx_info"),
+		get_tx_info_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.call_contract"),
+		call_contract_hint,
+	);
+	hint_processor.add_hint(
+		String::from("syscall_handler.library_call"),
+		library_call_hint,
+	);
+}
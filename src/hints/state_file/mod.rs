@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{
+	args::{felt_from_json, non_empty_args, string_arg, wrong_arguments},
+	contracts::ContractClass,
+	syscalls::{get_starknet_state, StarknetState},
+};
+use crate::{
+	cairo_run::COMPILE_OPTIONS_VAR_NAME,
+	compile::{self, compile_contract, CompileOptions},
+};
+
+#[derive(Error, Debug)]
+pub enum StateFileError {
+	#[error("failed to read state file {0}: {1}")]
+	Read(PathBuf, io::Error),
+	#[error("invalid state file {0}: {1}")]
+	Deserialize(PathBuf, serde_json::Error),
+	#[error("invalid state file {0}: '{1}' is not a felt")]
+	InvalidFelt(PathBuf, String),
+	#[error("failed to compile class {0:#x} of state file {1}: {2}")]
+	Compile(BigInt, PathBuf, compile::Error),
+	#[error("invalid class {0:#x} in state file {1}: {2}")]
+	InvalidClass(BigInt, PathBuf, VirtualMachineError),
+}
+
+/// Dump of a Starknet state, as read from a JSON state file:
+///
+/// ```json
+/// {
+///     "classes": { "0x1234": "contracts/token.cairo" },
+///     "contracts": {
+///         "0x42": { "class_hash": "0x1234", "storage": { "0x3a4e": "1000" } }
+///     }
+/// }
+/// ```
+///
+/// Classes are given by the path of their source, relative to the state file.
+/// Storage keys are storage addresses, as computed by `@storage_var`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct StateDump {
+	#[serde(default)]
+	classes: HashMap<String, PathBuf>,
+	#[serde(default)]
+	contracts: HashMap<String, ContractDump>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ContractDump {
+	class_hash: Value,
+	#[serde(default)]
+	storage: HashMap<String, Value>,
+}
+
+/// Load a JSON state file into the given state
+///
+/// The classes of the file are compiled and declared, its contracts are deployed without
+/// calling their constructor and their storage is written as is. Anything already in the state
+/// at the same class hash, contract address or storage address is overwritten.
+pub fn load_state_file(
+	state: &mut StarknetState,
+	path: &Path,
+	compile_options: &CompileOptions,
+) -> Result<(), StateFileError> {
+	let content = fs::read_to_string(path).map_err(|e| StateFileError::Read(path.into(), e))?;
+	let dump: StateDump =
+		serde_json::from_str(&content).map_err(|e| StateFileError::Deserialize(path.into(), e))?;
+	let felt = |value: &Value| {
+		felt_from_json(value)
+			.ok_or_else(|| StateFileError::InvalidFelt(path.into(), value.to_string()))
+	};
+	let felt_key = |key: &String| felt(&Value::String(key.clone()));
+
+	let directory = path.parent().unwrap_or_else(|| Path::new(""));
+	for (class_hash, source) in &dump.classes {
+		let class_hash = felt_key(class_hash)?;
+		let contract = compile_contract(&directory.join(source), compile_options)
+			.map_err(|e| StateFileError::Compile(class_hash.clone(), path.into(), e))?;
+		let class = ContractClass::new(contract)
+			.map_err(|e| StateFileError::InvalidClass(class_hash.clone(), path.into(), e))?;
+		state.classes.insert(class_hash, class);
+	}
+
+	for (contract_address, contract) in &dump.contracts {
+		let contract_address = felt_key(contract_address)?;
+		state.contracts.insert(contract_address.clone(), felt(&contract.class_hash)?);
+		for (address, value) in &contract.storage {
+			state.contract_storage_write(
+				contract_address.clone(),
+				felt_key(address)?,
+				felt(value)?,
+			);
+		}
+	}
+	Ok(())
+}
+
+/// Seed the Starknet state of the test with the content of a JSON state file
+///
+/// See `StateDump` for the format of the file. Its classes are compiled with the options of the
+/// run, like the ones of `--state-file`.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_liquidation{syscall_ptr: felt*}() {
+///     %{ load_state("tests/fixtures/mainnet_vault.json") %}
+///     ...
+/// }
+/// ```
+pub fn load_state(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let path = match non_empty_args(args)[..] {
+		[path] => PathBuf::from(string_arg(path)?),
+		_ => return Err(wrong_arguments("load_state(path)")),
+	};
+
+	let compile_options =
+		exec_scopes.get::<CompileOptions>(COMPILE_OPTIONS_VAR_NAME).unwrap_or_default();
+	load_state_file(get_starknet_state(exec_scopes)?, &path, &compile_options)
+		.map_err(|e| VirtualMachineError::CustomHint(e.to_string()))
+}
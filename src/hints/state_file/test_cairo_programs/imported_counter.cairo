@@ -0,0 +1,12 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+
+// Only found with `lib` in the cairo path
+from counter_storage import counter
+
+@view
+func get{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (value: felt) {
+    let (value) = counter.read();
+    return (value=value);
+}
@@ -0,0 +1,5 @@
+%lang starknet
+
+@storage_var
+func counter() -> (value: felt) {
+}
@@ -0,0 +1,45 @@
+%lang starknet
+
+@contract_interface
+namespace ICounter {
+    func increase(amount: felt) {
+    }
+
+    func get() -> (value: felt) {
+    }
+
+    func owner() -> (owner: felt) {
+    }
+}
+
+func test_load_state{syscall_ptr: felt*, range_check_ptr}() {
+    %{ load_state("src/hints/state_file/test_cairo_programs/state.json") %}
+
+    let (value) = ICounter.get(0x42);
+    assert 100 = value;
+    let (owner) = ICounter.owner(0x42);
+    assert 0x99 = owner;
+
+    ICounter.increase(0x42, 5);
+    let (value) = ICounter.get(0x42);
+    assert 105 = value;
+
+    // The storage of the test contract was seeded as well
+    tempvar counter;
+    %{ load(1, "counter", ids.counter) %}
+    assert 7 = counter;
+    return ();
+}
+
+func test_load_missing_state_file{syscall_ptr: felt*, range_check_ptr}() {
+    %{ load_state("src/hints/state_file/test_cairo_programs/missing.json") %}
+    return ();
+}
+
+func test_load_state_with_imports{syscall_ptr: felt*, range_check_ptr}() {
+    %{ load_state("src/hints/state_file/test_cairo_programs/state_with_import.json") %}
+
+    let (value) = ICounter.get(0x43);
+    assert 3 = value;
+    return ();
+}
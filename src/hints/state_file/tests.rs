@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+
+use assert_matches::assert_matches;
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use super::{load_state_file, StateFileError};
+use crate::{
+	cli::commands::test::{
+		tests::{run_single_test, run_single_test_with_options, test_options},
+		TestCommandError, TestOptions, TestStatus,
+	},
+	compile::CompileOptions,
+	hints::{storage_address, syscalls::StarknetState},
+};
+
+#[rstest]
+#[case("test_load_state", TestStatus::SUCCESS)]
+#[case("test_load_missing_state_file", TestStatus::FAILURE)]
+#[case("test_load_state_with_imports", TestStatus::FAILURE)]
+fn state_file(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/state_file/test_cairo_programs/state_file.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
+
+#[test]
+fn load_state_compiles_classes_with_the_options_of_the_run() -> Result<(), TestCommandError> {
+	let path = PathBuf::from("src/hints/state_file/test_cairo_programs/state_file.cairo");
+	let options = TestOptions {
+		compile_options: CompileOptions {
+			cairo_path: vec![PathBuf::from(
+				"src/hints/state_file/test_cairo_programs/lib",
+			)],
+			proof_mode: false,
+		},
+		..test_options(1000000)
+	};
+	let result = run_single_test_with_options("test_load_state_with_imports", &path, &options)?;
+	assert_eq!(TestStatus::SUCCESS, result.success, "{}", result.output);
+	Ok(())
+}
+
+#[test]
+fn load_state_file_seeds_contracts_and_storage() {
+	let mut state = StarknetState::default();
+	load_state_file(
+		&mut state,
+		Path::new("src/hints/state_file/test_cairo_programs/state.json"),
+		&CompileOptions::default(),
+	)
+	.unwrap();
+
+	assert!(state.classes.contains_key(&BigInt::from(0x1234)));
+	assert_eq!(
+		Some(&BigInt::from(0x1234)),
+		state.contracts.get(&BigInt::from(0x42))
+	);
+	assert_eq!(
+		BigInt::from(100),
+		state.contract_storage_read(&BigInt::from(0x42), &storage_address("counter", &[]))
+	);
+	assert_eq!(
+		BigInt::from(7),
+		state.contract_storage_read(&BigInt::from(1), &storage_address("counter", &[]))
+	);
+	// Deployed contracts do not collide with the loaded ones
+	assert!(!state.contracts.contains_key(&state.next_contract_address()));
+}
+
+#[test]
+fn load_state_file_rejects_invalid_felts() {
+	let path = std::env::temp_dir().join("cairo_foundry_invalid_state.json");
+	std::fs::write(&path, r#"{"contracts": {"0x42": {"class_hash": "hash"}}}"#).unwrap();
+
+	let result = load_state_file(
+		&mut StarknetState::default(),
+		&path,
+		&CompileOptions::default(),
+	);
+	assert_matches!(result, Err(StateFileError::InvalidFelt(_, value)) if value == "\"hash\"");
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, Sign};
+use sha3::{Digest, Keccak256};
+use starknet_crypto::{pedersen_hash, FieldElement};
+
+use super::{
+	args::{felt_arg, non_empty_args, output_var, reduce_felt, string_arg, wrong_arguments},
+	syscalls::get_starknet_state,
+};
+
+lazy_static! {
+	/// Storage addresses are reduced below 2^251 - 256
+	static ref ADDR_BOUND: BigInt = (BigInt::from(1) << 251) - BigInt::from(256);
+	/// `sn_keccak` keeps the 250 lowest bits of keccak256
+	static ref SN_KECCAK_MASK: BigInt = (BigInt::from(1) << 250) - BigInt::from(1);
+}
+
+/// Write a value to a storage variable of a contract, without calling the contract
+///
+/// Every argument between the variable name and the value is a key of the storage variable.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_transfer{syscall_ptr: felt*}() {
+///     %{ store(ids.token_address, "balances", ids.owner, 1000) %}
+///     ...
+/// }
+/// ```
+pub fn store(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (contract_address, variable_name, keys, value) = match non_empty_args(args)[..] {
+		[contract_address, variable_name, ref keys @ .., value] => (
+			felt_arg(contract_address, vm, ids_data, ap_tracking)?,
+			string_arg(variable_name)?,
+			keys.iter()
+				.map(|key| felt_arg(key, vm, ids_data, ap_tracking))
+				.collect::<Result<Vec<_>, _>>()?,
+			felt_arg(value, vm, ids_data, ap_tracking)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"store(contract_address, variable_name, keys..., value)",
+			)),
+	};
+
+	get_starknet_state(exec_scopes)?.contract_storage_write(
+		contract_address,
+		storage_address(variable_name, &keys),
+		value,
+	);
+	Ok(())
+}
+
+/// Read a storage variable of a contract, without calling the contract
+///
+/// Every argument between the variable name and the last one is a key of the storage variable.
+/// The value read is written to the cairo variable given as last argument.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_transfer{syscall_ptr: felt*}() {
+///     tempvar balance;
+///     %{ load(ids.token_address, "balances", ids.owner, ids.balance) %}
+///     assert 1000 = balance;
+/// }
+/// ```
+pub fn load(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let (contract_address, variable_name, keys, output) = match non_empty_args(args)[..] {
+		[contract_address, variable_name, ref keys @ .., output] => (
+			felt_arg(contract_address, vm, ids_data, ap_tracking)?,
+			string_arg(variable_name)?,
+			keys.iter()
+				.map(|key| felt_arg(key, vm, ids_data, ap_tracking))
+				.collect::<Result<Vec<_>, _>>()?,
+			output_var(output)?,
+		),
+		_ =>
+			return Err(wrong_arguments(
+				"load(contract_address, variable_name, keys..., ids.output)",
+			)),
+	};
+
+	let value = get_starknet_state(exec_scopes)?
+		.contract_storage_read(&contract_address, &storage_address(variable_name, &keys));
+	insert_value_from_var_name(output, value, vm, ids_data, ap_tracking)
+}
+
+/// Compute the address of a storage variable the way `@storage_var` does:
+/// `sn_keccak` of the variable name, hashed with each key using pedersen.
+pub fn storage_address(variable_name: &str, keys: &[BigInt]) -> BigInt {
+	let base = sn_keccak(variable_name.as_bytes());
+	if keys.is_empty() {
+		return base
+	}
+
+	let address = keys.iter().fold(to_field_element(&base), |address, key| {
+		pedersen_hash(&address, &to_field_element(key))
+	});
+	let address = from_field_element(&address);
+	if address >= *ADDR_BOUND {
+		address - &*ADDR_BOUND
+	} else {
+		address
+	}
+}
+
+/// Starknet keccak: keccak256 truncated to 250 bits so that it fits in a felt
+pub fn sn_keccak(data: &[u8]) -> BigInt {
+	let hash = Keccak256::digest(data);
+	BigInt::from_bytes_be(Sign::Plus, &hash) & &*SN_KECCAK_MASK
+}
+
+/// Convert a felt to a `FieldElement`, reducing it modulo the field prime
+pub(crate) fn to_field_element(value: &BigInt) -> FieldElement {
+	let (_, bytes) = reduce_felt(value).to_bytes_be();
+	let mut buffer = [0u8; 32];
+	buffer[32 - bytes.len()..].copy_from_slice(&bytes);
+	FieldElement::from_bytes_be(&buffer).expect("Should be reduced modulo the field prime")
+}
+
+pub(crate) fn from_field_element(value: &FieldElement) -> BigInt {
+	BigInt::from_bytes_be(Sign::Plus, &value.to_bytes_be())
+}
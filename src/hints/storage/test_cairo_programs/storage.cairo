@@ -0,0 +1,40 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+
+@storage_var
+func balance() -> (res: felt) {
+}
+
+@storage_var
+func allowances(owner: felt, spender: felt) -> (res: felt) {
+}
+
+func test_store{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    %{ store(1, "balance", 42) %}
+    let (res) = balance.read();
+    assert 42 = res;
+    return ();
+}
+
+func test_load{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    balance.write(21);
+    tempvar res;
+    %{ load(1, "balance", ids.res) %}
+    assert 21 = res;
+    return ();
+}
+
+func test_store_with_keys{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    tempvar owner = 0x123;
+    %{ store(1, "allowances", ids.owner, 0x456, 1000) %}
+    let (allowance) = allowances.read(0x123, 0x456);
+    assert 1000 = allowance;
+    let (other_allowance) = allowances.read(0x456, 0x123);
+    assert 0 = other_allowance;
+
+    tempvar loaded;
+    %{ load(1, "allowances", 0x123, 0x456, ids.loaded) %}
+    assert 1000 = loaded;
+    return ();
+}
@@ -0,0 +1,41 @@
+use num_bigint::BigInt;
+use num_traits::Num;
+use rstest::rstest;
+
+use super::{sn_keccak, storage_address};
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[test]
+fn storage_address_without_keys_is_sn_keccak() {
+	let expected = BigInt::from_str_radix(
+		"206f38f7e4f15e87567361213c28f235cccdaa1d7fd34c9db1dfe9489c6a091",
+		16,
+	)
+	.unwrap();
+
+	assert_eq!(expected, sn_keccak(b"balance"));
+	assert_eq!(expected, storage_address("balance", &[]));
+}
+
+#[test]
+fn storage_address_depends_on_keys() {
+	let first = storage_address("balances", &[BigInt::from(1)]);
+	let second = storage_address("balances", &[BigInt::from(2)]);
+
+	assert_ne!(first, second);
+	assert_ne!(sn_keccak(b"balances"), first);
+}
+
+#[rstest]
+#[case("test_store", TestStatus::SUCCESS)]
+#[case("test_load", TestStatus::SUCCESS)]
+#[case("test_store_with_keys", TestStatus::SUCCESS)]
+fn storage_cheatcodes(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/storage/test_cairo_programs/storage.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
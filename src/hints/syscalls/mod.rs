@@ -0,0 +1,343 @@
+#[cfg(test)]
+mod tests;
+
+mod state;
+pub use state::*;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_ptr_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::{exec_scope::ExecutionScopes, relocatable::Relocatable},
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use super::contracts;
+
+/// Implicit argument through which contracts make syscalls
+pub const SYSCALL_PTR_VAR_NAME: &str = "syscall_ptr";
+
+// Offsets of the syscall structs fields, as declared in `starkware.starknet.common.syscalls`.
+// Every request starts with the syscall selector.
+const STORAGE_ADDRESS_OFFSET: usize = 1;
+const STORAGE_VALUE_OFFSET: usize = 2;
+const RESPONSE_OFFSET: usize = 1;
+const EVENT_KEYS_LEN_OFFSET: usize = 1;
+const EVENT_KEYS_OFFSET: usize = 2;
+const EVENT_DATA_LEN_OFFSET: usize = 3;
+const EVENT_DATA_OFFSET: usize = 4;
+const CALL_TARGET_OFFSET: usize = 1;
+const CALL_SELECTOR_OFFSET: usize = 2;
+const CALL_CALLDATA_SIZE_OFFSET: usize = 3;
+const CALL_CALLDATA_OFFSET: usize = 4;
+const CALL_RETDATA_SIZE_OFFSET: usize = 5;
+const CALL_RETDATA_OFFSET: usize = 6;
+const MESSAGE_TO_ADDRESS_OFFSET: usize = 1;
+const MESSAGE_PAYLOAD_SIZE_OFFSET: usize = 2;
+const MESSAGE_PAYLOAD_OFFSET: usize = 3;
+
+/// Serve the `storage_read` syscall from the storage of the current contract
+///
+/// The hint is emitted by `starkware.starknet.common.syscalls.storage_read`,
+/// which is called by the `read` function of every `@storage_var`:
+///
+/// ```cairo
+/// %{ syscall_handler.storage_read(segments=segments, syscall_ptr=ids.syscall_ptr) %}
+/// ```
+pub fn storage_read(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let address = read_felt(vm, &(syscall_ptr + STORAGE_ADDRESS_OFFSET))?;
+
+	let value = get_starknet_state(exec_scopes)?.storage_read(&address);
+	vm.insert_value(&(syscall_ptr + STORAGE_VALUE_OFFSET), &value)?;
+	Ok(())
+}
+
+/// Serve the `storage_write` syscall, writing to the storage of the current contract
+pub fn storage_write(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let address = read_felt(vm, &(syscall_ptr + STORAGE_ADDRESS_OFFSET))?;
+	let value = read_felt(vm, &(syscall_ptr + STORAGE_VALUE_OFFSET))?;
+
+	get_starknet_state(exec_scopes)?.storage_write(address, value);
+	Ok(())
+}
+
+/// Serve the `emit_event` syscall, recording the event in the state
+pub fn emit_event(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let keys = read_felt_array(
+		vm,
+		&(syscall_ptr + EVENT_KEYS_LEN_OFFSET),
+		&(syscall_ptr + EVENT_KEYS_OFFSET),
+	)?;
+	let data = read_felt_array(
+		vm,
+		&(syscall_ptr + EVENT_DATA_LEN_OFFSET),
+		&(syscall_ptr + EVENT_DATA_OFFSET),
+	)?;
+
+	get_starknet_state(exec_scopes)?.emit_event(keys, data);
+	Ok(())
+}
+
+/// Serve the `send_message_to_l1` syscall, recording the message in the state
+pub fn send_message_to_l1(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let to_address = read_felt(vm, &(syscall_ptr + MESSAGE_TO_ADDRESS_OFFSET))?;
+	let payload = read_felt_array(
+		vm,
+		&(syscall_ptr + MESSAGE_PAYLOAD_SIZE_OFFSET),
+		&(syscall_ptr + MESSAGE_PAYLOAD_OFFSET),
+	)?;
+
+	get_starknet_state(exec_scopes)?.send_message_to_l1(to_address, payload);
+	Ok(())
+}
+
+/// Serve the `get_caller_address` syscall
+pub fn get_caller_address(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let caller_address = get_starknet_state(exec_scopes)?.current_caller_address().clone();
+	write_response(vm, ids_data, ap_tracking, &caller_address)
+}
+
+/// Serve the `get_contract_address` syscall
+pub fn get_contract_address(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let contract_address = get_starknet_state(exec_scopes)?.contract_address.clone();
+	write_response(vm, ids_data, ap_tracking, &contract_address)
+}
+
+/// Serve the `get_sequencer_address` syscall
+pub fn get_sequencer_address(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let sequencer_address = get_starknet_state(exec_scopes)?.sequencer_address.clone();
+	write_response(vm, ids_data, ap_tracking, &sequencer_address)
+}
+
+/// Serve the `get_block_number` syscall
+pub fn get_block_number(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let block_number = get_starknet_state(exec_scopes)?.block_number.clone();
+	write_response(vm, ids_data, ap_tracking, &block_number)
+}
+
+/// Serve the `get_block_timestamp` syscall
+pub fn get_block_timestamp(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let block_timestamp = get_starknet_state(exec_scopes)?.block_timestamp.clone();
+	write_response(vm, ids_data, ap_tracking, &block_timestamp)
+}
+
+/// Serve the `get_tx_info` syscall
+///
+/// The `TxInfo` struct and its signature are written to new segments.
+pub fn get_tx_info(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let tx_info = get_starknet_state(exec_scopes)?.tx_info.clone();
+
+	let signature_ptr = vm.add_memory_segment();
+	for (i, value) in tx_info.signature.iter().enumerate() {
+		vm.insert_value(&(signature_ptr + i), value)?;
+	}
+
+	// Fields of `starkware.starknet.common.syscalls.TxInfo`, in declaration order
+	let tx_info_ptr = vm.add_memory_segment();
+	vm.insert_value(&tx_info_ptr, &tx_info.version)?;
+	vm.insert_value(&(tx_info_ptr + 1), &tx_info.account_contract_address)?;
+	vm.insert_value(&(tx_info_ptr + 2), &tx_info.max_fee)?;
+	vm.insert_value(&(tx_info_ptr + 3), &BigInt::from(tx_info.signature.len()))?;
+	vm.insert_value(&(tx_info_ptr + 4), signature_ptr)?;
+	vm.insert_value(&(tx_info_ptr + 5), &tx_info.transaction_hash)?;
+	vm.insert_value(&(tx_info_ptr + 6), &tx_info.chain_id)?;
+	vm.insert_value(&(tx_info_ptr + 7), &tx_info.nonce)?;
+
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	vm.insert_value(&(syscall_ptr + RESPONSE_OFFSET), tx_info_ptr)?;
+	Ok(())
+}
+
+/// Serve the `call_contract` syscall by executing the called function of a contract
+/// deployed with the `deploy` cheatcode
+pub fn call_contract(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let (contract_address, selector, calldata) = read_call_request(vm, &syscall_ptr)?;
+
+	let retdata = contracts::call_contract(
+		exec_scopes,
+		*vm.get_current_step(),
+		&contract_address,
+		&selector,
+		&calldata,
+	)?;
+	write_call_response(vm, &syscall_ptr, &retdata)
+}
+
+/// Serve the `library_call` syscall by executing a function of a class declared with the
+/// `declare` cheatcode in the context of the current contract
+pub fn library_call(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	_args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	let (class_hash, selector, calldata) = read_call_request(vm, &syscall_ptr)?;
+
+	let retdata = contracts::library_call(
+		exec_scopes,
+		*vm.get_current_step(),
+		&class_hash,
+		&selector,
+		&calldata,
+	)?;
+	write_call_response(vm, &syscall_ptr, &retdata)
+}
+
+/// Read the target (contract address or class hash), the selector and the calldata of a
+/// `call_contract` or `library_call` request
+fn read_call_request(
+	vm: &VirtualMachine,
+	syscall_ptr: &Relocatable,
+) -> Result<(BigInt, BigInt, Vec<BigInt>), VirtualMachineError> {
+	Ok((
+		read_felt(vm, &(*syscall_ptr + CALL_TARGET_OFFSET))?,
+		read_felt(vm, &(*syscall_ptr + CALL_SELECTOR_OFFSET))?,
+		read_felt_array(
+			vm,
+			&(*syscall_ptr + CALL_CALLDATA_SIZE_OFFSET),
+			&(*syscall_ptr + CALL_CALLDATA_OFFSET),
+		)?,
+	))
+}
+
+fn write_call_response(
+	vm: &mut VirtualMachine,
+	syscall_ptr: &Relocatable,
+	retdata: &[BigInt],
+) -> Result<(), VirtualMachineError> {
+	let retdata_ptr = contracts::write_retdata(vm, retdata)?;
+	vm.insert_value(
+		&(*syscall_ptr + CALL_RETDATA_SIZE_OFFSET),
+		&BigInt::from(retdata.len()),
+	)?;
+	vm.insert_value(&(*syscall_ptr + CALL_RETDATA_OFFSET), retdata_ptr)?;
+	Ok(())
+}
+
+fn get_syscall_ptr(
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Relocatable, VirtualMachineError> {
+	get_ptr_from_var_name(SYSCALL_PTR_VAR_NAME, vm, ids_data, ap_tracking)
+}
+
+/// Write the single felt response of a syscall
+fn write_response(
+	vm: &mut VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	value: &BigInt,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_syscall_ptr(vm, ids_data, ap_tracking)?;
+	vm.insert_value(&(syscall_ptr + RESPONSE_OFFSET), value)?;
+	Ok(())
+}
+
+fn read_felt(vm: &VirtualMachine, address: &Relocatable) -> Result<BigInt, VirtualMachineError> {
+	Ok((*vm.get_integer(address)?).clone())
+}
+
+/// Read an array given the addresses of its length and of its pointer
+fn read_felt_array(
+	vm: &VirtualMachine,
+	len_address: &Relocatable,
+	ptr_address: &Relocatable,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let len = read_felt(vm, len_address)?
+		.to_usize()
+		.ok_or(VirtualMachineError::BigintToUsizeFail)?;
+	let ptr = vm.get_relocatable(ptr_address)?.into_owned();
+	(0..len).map(|i| read_felt(vm, &(ptr + i))).collect()
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use cairo_rs::{types::exec_scope::ExecutionScopes, vm::errors::vm_errors::VirtualMachineError};
+use num_bigint::BigInt;
+
+use crate::hints::contracts::ContractClass;
+
+pub const STARKNET_STATE_VAR_NAME: &str = "starknet_state";
+
+/// Address of the contract under test
+pub const TEST_CONTRACT_ADDRESS: u64 = 1;
+
+/// An event emitted through the `emit_event` syscall
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+	pub from_address: BigInt,
+	pub keys: Vec<BigInt>,
+	pub data: Vec<BigInt>,
+}
+
+/// A message sent to L1 through the `send_message_to_l1` syscall
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageToL1 {
+	pub from_address: BigInt,
+	pub to_address: BigInt,
+	pub payload: Vec<BigInt>,
+}
+
+/// Transaction information returned by the `get_tx_info` syscall
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxInfo {
+	pub version: BigInt,
+	pub account_contract_address: BigInt,
+	pub max_fee: BigInt,
+	pub signature: Vec<BigInt>,
+	pub transaction_hash: BigInt,
+	pub chain_id: BigInt,
+	pub nonce: BigInt,
+}
+
+/// In-memory state of the local Starknet the syscalls of a test are served from
+#[derive(Debug, Clone)]
+pub struct StarknetState {
+	/// Declared classes, indexed by class hash
+	pub classes: HashMap<BigInt, ContractClass>,
+	/// Class hash of each deployed contract, indexed by contract address
+	pub contracts: HashMap<BigInt, BigInt>,
+	/// Storage of each contract, indexed by contract address then by storage address
+	pub storage: HashMap<BigInt, HashMap<BigInt, BigInt>>,
+	pub events: Vec<Event>,
+	pub messages_to_l1: Vec<MessageToL1>,
+	pub caller_address: BigInt,
+	/// Caller address returned to any contract, set by `start_prank`
+	pub prank: Option<BigInt>,
+	/// Caller address returned to a given contract, set by `start_prank` with a target
+	pub targeted_pranks: HashMap<BigInt, BigInt>,
+	pub contract_address: BigInt,
+	pub sequencer_address: BigInt,
+	pub block_number: BigInt,
+	pub block_timestamp: BigInt,
+	pub tx_info: TxInfo,
+}
+
+impl Default for StarknetState {
+	fn default() -> Self {
+		Self {
+			classes: HashMap::new(),
+			contracts: HashMap::new(),
+			storage: HashMap::new(),
+			events: Vec::new(),
+			messages_to_l1: Vec::new(),
+			caller_address: BigInt::from(0),
+			prank: None,
+			targeted_pranks: HashMap::new(),
+			contract_address: BigInt::from(TEST_CONTRACT_ADDRESS),
+			sequencer_address: BigInt::from(0),
+			block_number: BigInt::from(0),
+			block_timestamp: BigInt::from(0),
+			tx_info: TxInfo::default(),
+		}
+	}
+}
+
+impl StarknetState {
+	/// Returns the caller address seen by the current contract, pranks included
+	pub fn current_caller_address(&self) -> &BigInt {
+		self.targeted_pranks
+			.get(&self.contract_address)
+			.or(self.prank.as_ref())
+			.unwrap_or(&self.caller_address)
+	}
+
+	/// Returns the address the next deployed contract will get
+	///
+	/// Addresses already taken by contracts loaded from a state file are skipped.
+	pub fn next_contract_address(&self) -> BigInt {
+		let mut address = BigInt::from(TEST_CONTRACT_ADDRESS) + self.contracts.len() + 1;
+		while self.contracts.contains_key(&address) {
+			address += 1;
+		}
+		address
+	}
+
+	/// Read a storage address of the current contract, unset addresses read as zero
+	pub fn storage_read(&self, address: &BigInt) -> BigInt {
+		self.contract_storage_read(&self.contract_address, address)
+	}
+
+	/// Write a storage address of the current contract
+	pub fn storage_write(&mut self, address: BigInt, value: BigInt) {
+		self.contract_storage_write(self.contract_address.clone(), address, value)
+	}
+
+	/// Read a storage address of any contract, unset addresses read as zero
+	pub fn contract_storage_read(&self, contract_address: &BigInt, address: &BigInt) -> BigInt {
+		self.storage
+			.get(contract_address)
+			.and_then(|storage| storage.get(address))
+			.cloned()
+			.unwrap_or_else(|| BigInt::from(0))
+	}
+
+	/// Write a storage address of any contract
+	pub fn contract_storage_write(
+		&mut self,
+		contract_address: BigInt,
+		address: BigInt,
+		value: BigInt,
+	) {
+		self.storage.entry(contract_address).or_default().insert(address, value);
+	}
+
+	/// Record an event emitted by the current contract
+	pub fn emit_event(&mut self, keys: Vec<BigInt>, data: Vec<BigInt>) {
+		self.events.push(Event {
+			from_address: self.contract_address.clone(),
+			keys,
+			data,
+		});
+	}
+
+	/// Record a message sent to L1 by the current contract
+	pub fn send_message_to_l1(&mut self, to_address: BigInt, payload: Vec<BigInt>) {
+		self.messages_to_l1.push(MessageToL1 {
+			from_address: self.contract_address.clone(),
+			to_address,
+			payload,
+		});
+	}
+}
+
+/// Returns the `StarknetState` of the current execution
+pub fn get_starknet_state(
+	exec_scopes: &mut ExecutionScopes,
+) -> Result<&mut StarknetState, VirtualMachineError> {
+	exec_scopes
+		.get_any_boxed_mut(STARKNET_STATE_VAR_NAME)?
+		.downcast_mut::<StarknetState>()
+		.ok_or_else(|| {
+			VirtualMachineError::VariableNotInScopeError(STARKNET_STATE_VAR_NAME.to_string())
+		})
+}
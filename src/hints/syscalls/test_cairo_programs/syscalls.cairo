@@ -0,0 +1,62 @@
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.starknet.common.syscalls import (
+    get_block_number,
+    get_block_timestamp,
+    get_caller_address,
+    get_contract_address,
+    get_tx_info,
+)
+
+@storage_var
+func balance() -> (res: felt) {
+}
+
+@event
+func balance_increased(amount: felt) {
+}
+
+@external
+func increase_balance{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    amount: felt
+) {
+    let (res) = balance.read();
+    balance.write(res + amount);
+    balance_increased.emit(amount);
+    return ();
+}
+
+func test_storage{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    let (initial_balance) = balance.read();
+    assert 0 = initial_balance;
+
+    increase_balance(42);
+    increase_balance(8);
+
+    let (res) = balance.read();
+    assert 50 = res;
+    return ();
+}
+
+func test_wrong_balance{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    increase_balance(42);
+
+    let (res) = balance.read();
+    assert 43 = res;
+    return ();
+}
+
+func test_execution_info{syscall_ptr: felt*}() {
+    let (caller_address) = get_caller_address();
+    assert 0 = caller_address;
+    let (contract_address) = get_contract_address();
+    assert 1 = contract_address;
+    let (block_number) = get_block_number();
+    assert 0 = block_number;
+    let (block_timestamp) = get_block_timestamp();
+    assert 0 = block_timestamp;
+    let (tx_info) = get_tx_info();
+    assert 0 = tx_info.signature_len;
+    return ();
+}
@@ -0,0 +1,17 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_storage", TestStatus::SUCCESS)]
+#[case("test_wrong_balance", TestStatus::FAILURE)]
+#[case("test_execution_info", TestStatus::SUCCESS)]
+fn syscalls(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/syscalls/test_cairo_programs/syscalls.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok").success;
+	assert_eq!(expected_success, result);
+	Ok(())
+}
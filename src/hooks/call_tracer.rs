@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use cairo_rs::{types::relocatable::Relocatable, vm::vm_core::VirtualMachine};
+
+use crate::io::compiled_programs::FunctionSignature;
+
+pub const CALL_TRACER_VAR_NAME: &str = "call_tracer";
+
+#[derive(Debug, Clone)]
+struct Frame {
+	return_size: usize,
+	start_step: usize,
+}
+
+/// Record the calls made during an execution, forge's `-vvvv` style.
+///
+/// Each call is rendered as a line of an indented tree when it is entered, with the name and
+/// arguments of the called function. The returned values and the number of steps spent inside
+/// the call are rendered when it returns.
+#[derive(Debug, Clone, Default)]
+pub struct CallTracer {
+	functions: HashMap<usize, FunctionSignature>,
+	frames: Vec<Frame>,
+	/// Depth of the tree the calls are rendered at, for programs called by another one
+	depth: usize,
+}
+
+impl CallTracer {
+	pub fn new(functions: HashMap<usize, FunctionSignature>) -> Self {
+		Self {
+			functions,
+			frames: Vec::new(),
+			depth: 0,
+		}
+	}
+
+	/// Returns a tracer for a program called from the current call, like a contract entry
+	/// point, whose calls are rendered below it in the tree
+	pub fn nested(&self, functions: HashMap<usize, FunctionSignature>) -> Self {
+		Self {
+			functions,
+			frames: Vec::new(),
+			depth: self.depth + self.frames.len(),
+		}
+	}
+
+	/// Called before a `call` instruction to `callee_pc` is executed.
+	///
+	/// Returns the line to append to the call tree.
+	pub fn enter(&mut self, vm: &VirtualMachine, callee_pc: usize) -> String {
+		let (name, args_size, return_size) = match self.functions.get(&callee_pc) {
+			Some(function) => (
+				function.name.clone(),
+				function.args_size,
+				function.return_size,
+			),
+			None => (format!("<pc {callee_pc}>"), 0, 0),
+		};
+
+		let line = format!(
+			"{}├─ {}({})\n",
+			"│  ".repeat(self.depth + self.frames.len()),
+			name,
+			read_last_pushed(vm, args_size).join(", ")
+		);
+
+		self.frames.push(Frame {
+			return_size,
+			start_step: *vm.get_current_step(),
+		});
+
+		line
+	}
+
+	/// Called before a `ret` instruction is executed.
+	///
+	/// Returns the line to append to the call tree, or `None` if the returning function
+	/// was not entered through a `call` instruction (i.e. it is the entrypoint).
+	pub fn exit(&mut self, vm: &VirtualMachine) -> Option<String> {
+		let frame = self.frames.pop()?;
+
+		Some(format!(
+			"{}└─ ← ({}) [{} steps]\n",
+			"│  ".repeat(self.depth + self.frames.len() + 1),
+			read_last_pushed(vm, frame.return_size).join(", "),
+			*vm.get_current_step() - frame.start_step + 1
+		))
+	}
+}
+
+/// Read the `size` last values pushed on the stack, oldest first
+fn read_last_pushed(vm: &VirtualMachine, size: usize) -> Vec<String> {
+	let ap = vm.get_ap();
+	(1..=size)
+		.rev()
+		.map(|i| match ap.offset.checked_sub(i) {
+			Some(offset) => format_cell(
+				vm,
+				&Relocatable {
+					segment_index: ap.segment_index,
+					offset,
+				},
+			),
+			None => String::from("?"),
+		})
+		.collect()
+}
+
+fn format_cell(vm: &VirtualMachine, address: &Relocatable) -> String {
+	match vm.get_integer(address) {
+		Ok(value) => value.to_string(),
+		Err(_) => match vm.get_relocatable(address) {
+			Ok(ptr) => format!("{}:{}", ptr.segment_index, ptr.offset),
+			Err(_) => String::from("?"),
+		},
+	}
+}
@@ -1,19 +1,37 @@
 #[cfg(test)]
 mod tests;
 
-use std::{collections::HashMap, ops::Add};
+mod call_tracer;
+pub use call_tracer::*;
+
+use std::{collections::HashMap, ops::Add, sync::Arc};
 
 use cairo_rs::{
 	types::{exec_scope::ExecutionScopes, instruction::Opcode},
-	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+	vm::{errors::vm_errors::VirtualMachineError, hook::Hooks, vm_core::VirtualMachine},
 };
 use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use uuid::Uuid;
 
-use crate::hints::MOCK_CALL_KEY;
+use crate::hints::{
+	output_buffer::{write_to_output_buffer, EXECUTION_UUID_VAR_NAME},
+	MOCK_CALL_KEY,
+};
 
 pub const HOOKS_VAR_NAME: &str = "hooks";
 pub const MAX_STEPS_VAR_NAME: &str = "max_steps";
 
+/// Create a new ``Hooks`` object, with the followings hooks:
+/// - pre_step_instruction
+/// - post_step_instruction
+pub fn setup_hooks() -> Hooks {
+	Hooks::new(
+		Arc::new(pre_step_instruction),
+		Arc::new(post_step_instruction),
+	)
+}
+
 /// Called before an instruction is executed by the virtual machine (VM).
 ///
 /// This function is called before the VM will execute an instruction.
@@ -25,6 +43,9 @@ pub const MAX_STEPS_VAR_NAME: &str = "max_steps";
 ///
 /// `mock_call` hint is using `pre_step_instruction` to modify
 /// VM allocation pointer (ap) and program counter (pc) in order to return mocked value.
+///
+/// When a `CallTracer` is in scope, every (non mocked) call and return is also appended
+/// to the execution output buffer.
 pub fn pre_step_instruction(
 	vm: &mut VirtualMachine,
 	exec_scopes: &mut ExecutionScopes,
@@ -54,6 +75,15 @@ pub fn pre_step_instruction(
 			vm.set_pc(pc.add(2));
 			vm.set_ap(ap.offset + 1);
 			vm.skip_next_instruction_execution();
+			return Ok(())
+		}
+
+		if let Some(line) = call_tracer(exec_scopes).map(|tracer| tracer.enter(vm, new_pc.offset)) {
+			write_to_execution_output(exec_scopes, &line)?;
+		}
+	} else if instruction.opcode == Opcode::Ret {
+		if let Some(line) = call_tracer(exec_scopes).and_then(|tracer| tracer.exit(vm)) {
+			write_to_execution_output(exec_scopes, &line)?;
 		}
 	}
 
@@ -90,3 +120,26 @@ pub fn ensure_max_steps_not_reached(
 		Ok(())
 	}
 }
+
+fn call_tracer(exec_scopes: &mut ExecutionScopes) -> Option<&mut CallTracer> {
+	exec_scopes
+		.get_any_boxed_mut(CALL_TRACER_VAR_NAME)
+		.ok()?
+		.downcast_mut::<CallTracer>()
+}
+
+/// Append `data` to the output buffer of the current execution
+fn write_to_execution_output(
+	exec_scopes: &ExecutionScopes,
+	data: &str,
+) -> Result<(), VirtualMachineError> {
+	let execution_uuid = exec_scopes
+		.get::<BigInt>(EXECUTION_UUID_VAR_NAME)?
+		.to_u128()
+		.map(Uuid::from_u128)
+		.ok_or_else(|| {
+			VirtualMachineError::VariableNotInScopeError(EXECUTION_UUID_VAR_NAME.to_string())
+		})?;
+	write_to_output_buffer(&execution_uuid, data);
+	Ok(())
+}
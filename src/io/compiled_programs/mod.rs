@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, io};
+
+use cairo_rs::serde::deserialize_program::{Identifier, ProgramJson};
+use regex::Regex;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ListTestEntrypointsError {
+	#[error("Failed to read the compiled cairo program file")]
+	FailedToReadCompiledProgram(#[from] io::Error),
+	#[error("Failed to parse the content of the compiled cairo program as json")]
+	InvalidCompiledProgramFormat(#[from] serde_json::Error),
+}
+
+/// Name and frame layout of a function found in a compiled cairo program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+	/// Name of the function, without the `__main__.` prefix
+	pub name: String,
+	/// Number of felts taken as explicit arguments
+	pub args_size: usize,
+	/// Number of felts returned, implicit arguments excluded
+	pub return_size: usize,
+}
+
+/// Get the list of test entrypoint from a compiled cairo file.
+/// test entrypoint are function starting with "test_", or "invariant_" for invariant tests.
+/// The function will return a list of test entrypoint as `String` (ie: "test_function");
+///
+/// return a vector of entrypoints
+pub fn list_test_entrypoints(
+	program_json: &ProgramJson,
+) -> Result<Vec<String>, ListTestEntrypointsError> {
+	let re = Regex::new(r"__main__.((?:test|invariant)_\w+)$").expect("Should be a valid regex");
+	let mut test_entrypoints = Vec::new();
+
+	for (key, value) in program_json.identifiers.iter() {
+		if re.is_match(key) && value.type_ == Some("function".into()) {
+			// capture 0 refers to the whole match
+			// capture n-1 refers to the next to last match
+			// captures are denoted with () in regex
+			for capture in re.captures_iter(key) {
+				// regex __main__.((?:test|invariant)_\w+)$ has 2 captures
+				// capture 0 is the whole match
+				// capture 1 is the first (and last) capture in this regex
+				test_entrypoints.push(capture[1].to_string());
+			}
+		}
+	}
+	Ok(test_entrypoints)
+}
+
+/// Get the signature of every function of a compiled cairo program, indexed by the pc of the
+/// function's first instruction.
+///
+/// Sizes are computed from the `Args` and `Return` identifiers the compiler generates for each
+/// function.
+pub fn list_functions(program_json: &ProgramJson) -> HashMap<usize, FunctionSignature> {
+	let identifiers = &program_json.identifiers;
+
+	identifiers
+		.iter()
+		.filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+		.filter_map(|(full_name, identifier)| {
+			let pc = identifier.pc?;
+			let name = full_name.strip_prefix("__main__.").unwrap_or(full_name).to_string();
+			let args_size = identifiers
+				.get(&format!("{full_name}.Args"))
+				.map_or(0, |args| identifier_size(identifiers, args));
+			let return_size = identifiers
+				.get(&format!("{full_name}.Return"))
+				.map_or(0, |ret| identifier_size(identifiers, ret));
+
+			Some((
+				pc,
+				FunctionSignature {
+					name,
+					args_size,
+					return_size,
+				},
+			))
+		})
+		.collect()
+}
+
+/// Get the implicit arguments of a function, in the order they are expected on the stack.
+///
+/// `function` is the full name of the function (ie: "__main__.test_function").
+pub fn implicit_args(identifiers: &HashMap<String, Identifier>, function: &str) -> Vec<String> {
+	let mut members: Vec<_> = identifiers
+		.get(&format!("{function}.ImplicitArgs"))
+		.and_then(|implicit_args| implicit_args.members.as_ref())
+		.map(|members| members.iter().collect())
+		.unwrap_or_default();
+	members.sort_by_key(|(_, member)| member.offset);
+	members.into_iter().map(|(name, _)| name.clone()).collect()
+}
+
+/// Get the explicit arguments of a function as `(name, cairo_type)`, in declaration order.
+///
+/// `function` is the full name of the function (ie: "__main__.test_function").
+pub fn explicit_args(
+	identifiers: &HashMap<String, Identifier>,
+	function: &str,
+) -> Vec<(String, String)> {
+	let mut members: Vec<_> = identifiers
+		.get(&format!("{function}.Args"))
+		.and_then(|args| args.members.as_ref())
+		.map(|members| members.iter().collect())
+		.unwrap_or_default();
+	members.sort_by_key(|(_, member)| member.offset);
+	members
+		.into_iter()
+		.map(|(name, member)| (name.clone(), member.cairo_type.clone()))
+		.collect()
+}
+
+/// Size, in felts, of a struct or type definition identifier.
+///
+/// Members whose type cannot be resolved are counted as a single felt.
+pub fn identifier_size(
+	identifiers: &HashMap<String, Identifier>,
+	identifier: &Identifier,
+) -> usize {
+	match (&identifier.members, &identifier.cairo_type) {
+		(Some(members), _) => members
+			.values()
+			.map(|member| member.offset + type_size(identifiers, &member.cairo_type).unwrap_or(1))
+			.max()
+			.unwrap_or(0),
+		(None, Some(cairo_type)) => type_size(identifiers, cairo_type).unwrap_or(1),
+		(None, None) => 0,
+	}
+}
+
+/// Size, in felts, of a cairo type.
+///
+/// Supports felts, pointers, (named) tuples and structs declared in the program.
+/// Returns `None` if the type cannot be resolved.
+pub fn type_size(identifiers: &HashMap<String, Identifier>, cairo_type: &str) -> Option<usize> {
+	let cairo_type = cairo_type.trim();
+	if cairo_type == "felt" || cairo_type.ends_with('*') {
+		return Some(1)
+	}
+
+	if let Some(tuple) = cairo_type.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+		return split_tuple_members(tuple)
+			.into_iter()
+			.map(|member| {
+				// Named tuple members are written as `name: type`
+				let member_type = member.split_once(':').map_or(member, |(_, t)| t);
+				type_size(identifiers, member_type)
+			})
+			.sum()
+	}
+
+	identifiers
+		.get(cairo_type)
+		.map(|identifier| identifier_size(identifiers, identifier))
+}
+
+/// Split the members of a tuple type on top-level commas only
+fn split_tuple_members(tuple: &str) -> Vec<&str> {
+	let mut members = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+	for (i, c) in tuple.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => {
+				members.push(&tuple[start..i]);
+				start = i + 1;
+			},
+			_ => {},
+		}
+	}
+	if !tuple[start..].trim().is_empty() {
+		members.push(&tuple[start..]);
+	}
+	members
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use cairo_rs::serde::deserialize_program::Identifier;
+use serde_json::json;
+
+use super::{explicit_args, implicit_args, type_size};
+
+#[test]
+fn type_size_of_felts_pointers_and_tuples() {
+	let identifiers = HashMap::new();
+
+	assert_eq!(Some(1), type_size(&identifiers, "felt"));
+	assert_eq!(Some(1), type_size(&identifiers, "__main__.Point*"));
+	assert_eq!(Some(0), type_size(&identifiers, "()"));
+	assert_eq!(
+		Some(4),
+		type_size(&identifiers, "(res: felt, ptr: felt*, pair: (felt, felt))")
+	);
+}
+
+#[test]
+fn type_size_of_unknown_struct() {
+	assert_eq!(None, type_size(&HashMap::new(), "__main__.Unknown"));
+}
+
+#[test]
+fn implicit_args_are_ordered_by_offset() {
+	let implicit_args_struct: Identifier = serde_json::from_value(json!({
+		"type": "struct",
+		"full_name": "__main__.test_storage.ImplicitArgs",
+		"members": {
+			"range_check_ptr": { "cairo_type": "felt", "offset": 2 },
+			"syscall_ptr": { "cairo_type": "felt*", "offset": 0 },
+			"pedersen_ptr": {
+				"cairo_type": "starkware.cairo.common.cairo_builtins.HashBuiltin*",
+				"offset": 1
+			}
+		},
+		"size": 3
+	}))
+	.unwrap();
+	let identifiers = HashMap::from([(
+		String::from("__main__.test_storage.ImplicitArgs"),
+		implicit_args_struct,
+	)]);
+
+	assert_eq!(
+		vec!["syscall_ptr", "pedersen_ptr", "range_check_ptr"],
+		implicit_args(&identifiers, "__main__.test_storage")
+	);
+	assert!(implicit_args(&identifiers, "__main__.test_other").is_empty());
+}
+
+#[test]
+fn explicit_args_are_ordered_by_offset() {
+	let args_struct: Identifier = serde_json::from_value(json!({
+		"type": "struct",
+		"full_name": "__main__.test_transfer.Args",
+		"members": {
+			"to": { "cairo_type": "felt", "offset": 1 },
+			"amount": { "cairo_type": "felt", "offset": 0 }
+		},
+		"size": 2
+	}))
+	.unwrap();
+	let identifiers = HashMap::from([(String::from("__main__.test_transfer.Args"), args_struct)]);
+
+	assert_eq!(
+		vec![
+			(String::from("amount"), String::from("felt")),
+			(String::from("to"), String::from("felt"))
+		],
+		explicit_args(&identifiers, "__main__.test_transfer")
+	);
+	assert!(explicit_args(&identifiers, "__main__.test_other").is_empty());
+}
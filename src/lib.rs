@@ -3,6 +3,9 @@
 pub mod cairo_run;
 pub mod cli;
 pub mod compile;
+pub mod config;
+pub mod fuzz;
 pub mod hints;
 pub mod hooks;
 pub mod io;
+pub mod trace;
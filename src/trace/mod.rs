@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+};
+
+use cairo_rs::vm::runners::cairo_runner::CairoRunner;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+/// Offsets of an instruction are encoded on 16 bits with a bias of 2^15
+const OFFSET_BIAS: i64 = 1 << 15;
+const OFFSET_MASK: u64 = 0xffff;
+const FLAGS_SHIFT: u64 = 48;
+
+const DST_REG_FLAG: u64 = 1;
+const OP0_REG_FLAG: u64 = 1 << 1;
+const OP1_IMM_FLAG: u64 = 1 << 2;
+const OP1_FP_FLAG: u64 = 1 << 3;
+const OP1_AP_FLAG: u64 = 1 << 4;
+
+/// Memory values are written as 32 bytes little endian integers in binary memory files
+const FELT_BYTES: usize = 32;
+
+/// A single step of a relocated execution trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+	pub pc: usize,
+	pub ap: usize,
+	pub fp: usize,
+}
+
+/// Memory addresses accessed by the instruction executed at a given step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessedAddresses {
+	pub dst: usize,
+	pub op0: usize,
+	pub op1: usize,
+}
+
+impl AccessedAddresses {
+	pub fn contains(&self, address: usize) -> bool {
+		self.dst == address || self.op0 == address || self.op1 == address
+	}
+}
+
+/// The relocated trace and memory of a whole execution.
+///
+/// Once relocated, every address is a plain integer and every pointer stored in memory
+/// can be followed without knowing the segments layout, which is what makes it possible
+/// to step backwards through an execution that is already over.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionTrace {
+	pub entries: Vec<TraceEntry>,
+	pub memory: Vec<Option<BigInt>>,
+}
+
+impl ExecutionTrace {
+	/// Build an `ExecutionTrace` from a runner on which `relocate` has been called.
+	///
+	/// The trace will be empty if the `VirtualMachine` was created with tracing disabled.
+	pub fn from_runner(cairo_runner: &CairoRunner) -> Self {
+		let entries = cairo_runner
+			.relocated_trace
+			.iter()
+			.flatten()
+			.map(|entry| TraceEntry {
+				pc: entry.pc,
+				ap: entry.ap,
+				fp: entry.fp,
+			})
+			.collect();
+
+		Self {
+			entries,
+			memory: cairo_runner.relocated_memory.clone(),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the value stored at the given relocated address, if any
+	pub fn memory_at(&self, address: usize) -> Option<&BigInt> {
+		self.memory.get(address).and_then(|value| value.as_ref())
+	}
+
+	/// Returns the addresses of `dst`, `op0` and `op1` for the instruction executed at `step`.
+	///
+	/// Returns `None` if the step does not exist or if the instruction (or the `op0` pointer it
+	/// dereferences) cannot be found in the relocated memory.
+	pub fn accessed_addresses(&self, step: usize) -> Option<AccessedAddresses> {
+		let entry = self.entries.get(step)?;
+		let encoded = self.memory_at(entry.pc)?.to_u64()?;
+
+		let off_dst = decode_offset(encoded, 0);
+		let off_op0 = decode_offset(encoded, 16);
+		let off_op1 = decode_offset(encoded, 32);
+		let flags = encoded >> FLAGS_SHIFT;
+
+		let dst_base = if flags & DST_REG_FLAG == 0 {
+			entry.ap
+		} else {
+			entry.fp
+		};
+		let op0_base = if flags & OP0_REG_FLAG == 0 {
+			entry.ap
+		} else {
+			entry.fp
+		};
+		let dst = apply_offset(dst_base, off_dst)?;
+		let op0 = apply_offset(op0_base, off_op0)?;
+
+		let op1_base = if flags & OP1_IMM_FLAG != 0 {
+			entry.pc
+		} else if flags & OP1_FP_FLAG != 0 {
+			entry.fp
+		} else if flags & OP1_AP_FLAG != 0 {
+			entry.ap
+		} else {
+			self.memory_at(op0)?.to_usize()?
+		};
+		let op1 = apply_offset(op1_base, off_op1)?;
+
+		Some(AccessedAddresses { dst, op0, op1 })
+	}
+
+	/// Returns the first step accessing the given address.
+	///
+	/// Cairo memory is write-once, so the first instruction accessing a cell is the one that
+	/// assigned it (unless it was written by a hint, in which case it is the first instruction
+	/// reading the value computed by this hint).
+	pub fn first_access(&self, address: usize) -> Option<usize> {
+		(0..self.entries.len()).find(|step| {
+			self.accessed_addresses(*step)
+				.map(|accessed| accessed.contains(address))
+				.unwrap_or(false)
+		})
+	}
+
+	/// Write the trace in the cairo-lang binary format.
+	///
+	/// Each entry is written as its `ap`, `fp` and `pc` registers, in this order,
+	/// encoded as 8 bytes little endian integers.
+	pub fn write_binary_trace(&self, path: &Path) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		for entry in self.entries.iter() {
+			for register in [entry.ap, entry.fp, entry.pc] {
+				writer.write_all(&(register as u64).to_le_bytes())?;
+			}
+		}
+		writer.flush()
+	}
+
+	/// Write the memory in the cairo-lang binary format.
+	///
+	/// Each assigned cell is written as its address, encoded as a 8 bytes little endian
+	/// integer, followed by its value, encoded as a 32 bytes little endian integer.
+	pub fn write_binary_memory(&self, path: &Path) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		for (address, value) in self.memory.iter().enumerate() {
+			if let Some(value) = value {
+				let (_, mut bytes) = value.to_bytes_le();
+				bytes.resize(FELT_BYTES, 0);
+				writer.write_all(&(address as u64).to_le_bytes())?;
+				writer.write_all(&bytes)?;
+			}
+		}
+		writer.flush()
+	}
+}
+
+fn decode_offset(encoded: u64, shift: u64) -> i64 {
+	((encoded >> shift) & OFFSET_MASK) as i64 - OFFSET_BIAS
+}
+
+fn apply_offset(base: usize, offset: i64) -> Option<usize> {
+	usize::try_from(base as i64 + offset).ok()
+}
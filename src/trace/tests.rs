@@ -0,0 +1,96 @@
+use std::fs;
+
+use num_bigint::BigInt;
+
+use super::{AccessedAddresses, ExecutionTrace, TraceEntry};
+
+/// Build a trace with a single step at `pc = 1`, `ap = fp = 10`
+fn single_step_trace(encoded_instruction: u64, mut cells: Vec<(usize, u64)>) -> ExecutionTrace {
+	let mut memory = vec![None; 32];
+	cells.push((1, encoded_instruction));
+	for (address, value) in cells {
+		memory[address] = Some(BigInt::from(value));
+	}
+
+	ExecutionTrace {
+		entries: vec![TraceEntry {
+			pc: 1,
+			ap: 10,
+			fp: 10,
+		}],
+		memory,
+	}
+}
+
+#[test]
+fn accessed_addresses_with_immediate_operand() {
+	// [ap] = 5, ap++
+	let trace = single_step_trace(0x480680017fff8000, vec![(2, 5), (10, 5)]);
+
+	assert_eq!(
+		Some(AccessedAddresses {
+			dst: 10,
+			op0: 9,
+			op1: 2
+		}),
+		trace.accessed_addresses(0)
+	);
+}
+
+#[test]
+fn accessed_addresses_with_op1_relative_to_op0() {
+	// [ap] = [[ap - 1] + 2]
+	let encoded = (0x4000 << 48) | (0x8002 << 32) | (0x7fff << 16) | 0x8000;
+	let trace = single_step_trace(encoded, vec![(9, 20), (22, 7), (10, 7)]);
+
+	assert_eq!(
+		Some(AccessedAddresses {
+			dst: 10,
+			op0: 9,
+			op1: 22
+		}),
+		trace.accessed_addresses(0)
+	);
+}
+
+#[test]
+fn first_access_finds_the_writing_step() {
+	let trace = single_step_trace(0x480680017fff8000, vec![(2, 5), (10, 5)]);
+
+	assert_eq!(Some(0), trace.first_access(10));
+	assert_eq!(None, trace.first_access(11));
+}
+
+#[test]
+fn accessed_addresses_of_unknown_step() {
+	let trace = ExecutionTrace::default();
+
+	assert!(trace.is_empty());
+	assert_eq!(None, trace.accessed_addresses(0));
+}
+
+#[test]
+fn write_binary_trace_and_memory() {
+	let trace = single_step_trace(0x480680017fff8000, vec![(2, 5)]);
+	let dir = std::env::temp_dir().join("cairo_foundry_test");
+	fs::create_dir_all(&dir).unwrap();
+	let trace_path = dir.join("write_binary_trace.bin");
+	let memory_path = dir.join("write_binary_memory.bin");
+
+	trace.write_binary_trace(&trace_path).unwrap();
+	trace.write_binary_memory(&memory_path).unwrap();
+
+	let mut expected_trace = Vec::new();
+	for register in [10_u64, 10, 1] {
+		expected_trace.extend(register.to_le_bytes());
+	}
+	assert_eq!(expected_trace, fs::read(&trace_path).unwrap());
+
+	let memory = fs::read(&memory_path).unwrap();
+	assert_eq!(2 * 40, memory.len());
+	assert_eq!(1_u64.to_le_bytes(), memory[..8]);
+	assert_eq!(0x480680017fff8000_u64.to_le_bytes(), memory[8..16]);
+	assert_eq!(vec![0; 24], memory[16..40]);
+	assert_eq!(2_u64.to_le_bytes(), memory[40..48]);
+	assert_eq!(5, memory[48]);
+}
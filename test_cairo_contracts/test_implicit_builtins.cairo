@@ -0,0 +1,33 @@
+// No %builtins directive: the builtins are inferred from the implicit arguments of each test
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.cairo.common.hash import hash2
+from starkware.cairo.common.math import assert_nn
+
+func test_pedersen{pedersen_ptr: HashBuiltin*}() {
+    let (hash) = hash2{hash_ptr=pedersen_ptr}(1, 2);
+    let (same_hash) = hash2{hash_ptr=pedersen_ptr}(1, 2);
+    assert hash = same_hash;
+    return ();
+}
+
+func test_range_check{range_check_ptr}() {
+    assert_nn(42);
+    return ();
+}
+
+// Implicit arguments in another order than the layouts define the builtins
+func test_range_check_and_pedersen{range_check_ptr, pedersen_ptr: HashBuiltin*}() {
+    assert_nn(42);
+    let (hash) = hash2{hash_ptr=pedersen_ptr}(1, 2);
+    let (same_hash) = hash2{hash_ptr=pedersen_ptr}(1, 2);
+    assert hash = same_hash;
+    return ();
+}
+
+// Values written to the range check segment are checked by the builtin
+func test_range_check_is_enforced{range_check_ptr}() {
+    assert [range_check_ptr] = -1;
+    let range_check_ptr = range_check_ptr + 1;
+    return ();
+}
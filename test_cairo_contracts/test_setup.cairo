@@ -0,0 +1,52 @@
+%lang starknet
+
+from starkware.cairo.common.registers import get_label_location
+from starkware.starknet.common.syscalls import get_block_timestamp
+
+func __setup__{syscall_ptr: felt*}() {
+    %{ store(1, "balance", 100) %}
+    %{ warp(1672531200) %}
+
+    let mock_ret_value = 42;
+    let func_to_mock = get_label_location(mocked_func);
+    %{ mock_call(func_to_mock, mock_ret_value) %}
+    return ();
+}
+
+func mocked_func() -> felt {
+    assert 21 = 42;
+    return 21;
+}
+
+func test_storage_is_set_up{syscall_ptr: felt*}() {
+    tempvar balance;
+    %{ load(1, "balance", ids.balance) %}
+    assert 100 = balance;
+
+    let (block_timestamp) = get_block_timestamp();
+    assert 1672531200 = block_timestamp;
+    return ();
+}
+
+func test_mocks_are_set_up() {
+    let x = mocked_func();
+    assert 42 = x;
+    return ();
+}
+
+// Each test starts from the state left by the setup, whatever the order of the tests
+func test_state_is_not_shared_1{syscall_ptr: felt*}() {
+    tempvar balance;
+    %{ load(1, "balance", ids.balance) %}
+    assert 100 = balance;
+    %{ store(1, "balance", 200) %}
+    return ();
+}
+
+func test_state_is_not_shared_2{syscall_ptr: felt*}() {
+    tempvar balance;
+    %{ load(1, "balance", ids.balance) %}
+    assert 100 = balance;
+    %{ store(1, "balance", 300) %}
+    return ();
+}
@@ -0,0 +1,12 @@
+%lang starknet
+
+// Expectations only apply to the execution they are set in, the setup cannot set any
+func __setup__() {
+    %{ expect_revert() %}
+    assert 1 = 2;
+    return ();
+}
+
+func test_not_run() {
+    return ();
+}